@@ -0,0 +1,520 @@
+use std::fmt;
+
+use cargo_metadata::diagnostic::DiagnosticLevel;
+
+use crate::{
+    diagnostic::{Diagnostic, Span},
+    error::{Error, Result},
+};
+
+const ANNOTATION_PREFIX: &str = "//~";
+const SPAN_MARKER: &str = "/*^*/";
+
+/// The level of an annotated diagnostic.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub(crate) enum AnnotationLevel {
+    Error,
+    Warning,
+    Help,
+    Note,
+}
+
+impl AnnotationLevel {
+    fn parse(level: &str) -> Option<Self> {
+        match level {
+            "ERROR" => Some(Self::Error),
+            "WARN" | "WARNING" => Some(Self::Warning),
+            "HELP" => Some(Self::Help),
+            "NOTE" => Some(Self::Note),
+            _ => None,
+        }
+    }
+
+    fn matches(&self, level: &DiagnosticLevel) -> bool {
+        matches!(
+            (self, level),
+            (Self::Error, DiagnosticLevel::Error | DiagnosticLevel::Ice)
+                | (Self::Warning, DiagnosticLevel::Warning)
+                | (Self::Help, DiagnosticLevel::Help)
+                | (Self::Note, DiagnosticLevel::Note)
+        )
+    }
+}
+
+impl fmt::Display for AnnotationLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Error => write!(f, "ERROR"),
+            Self::Warning => write!(f, "WARN"),
+            Self::Help => write!(f, "HELP"),
+            Self::Note => write!(f, "NOTE"),
+        }
+    }
+}
+
+/// A (1-based) position within a test file, with columns counted in chars (like rustc).
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub(crate) struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// The expected range of a diagnostic's primary span (with an exclusive end),
+/// as declared via `[<line>:<column>-<line>:<column>]` or `[<column>-<column>]`
+/// (or just its start, via `[<line>:<column>]` or `[<column>]`), or `/*^*/` markers.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub(crate) struct SpanRange {
+    pub start: Position,
+    pub end: Option<Position>,
+}
+
+impl SpanRange {
+    // Positions without a line refer to the annotated line:
+    fn parse(range: &str, line: usize) -> Option<Self> {
+        let position = |position: &str| -> Option<Position> {
+            match position.trim().split_once(':') {
+                Some((line, column)) => Some(Position {
+                    line: line.trim().parse().ok()?,
+                    column: column.trim().parse().ok()?,
+                }),
+                None => Some(Position {
+                    line,
+                    column: position.trim().parse().ok()?,
+                }),
+            }
+        };
+
+        match range.split_once('-') {
+            Some((start, end)) => Some(Self {
+                start: position(start)?,
+                end: Some(position(end)?),
+            }),
+            None => Some(Self {
+                start: position(range)?,
+                end: None,
+            }),
+        }
+    }
+
+    fn matches(&self, span: &Span) -> bool {
+        let start = Position {
+            line: span.line_start,
+            column: span.column_start,
+        };
+        let end = Position {
+            line: span.line_end,
+            column: span.column_end,
+        };
+        self.start == start && self.end.is_none_or(|expected| expected == end)
+    }
+}
+
+impl fmt::Display for SpanRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.end {
+            Some(end) => write!(f, "{}-{}", self.start, end),
+            None => write!(f, "{}", self.start),
+        }
+    }
+}
+
+impl From<&Span> for SpanRange {
+    fn from(span: &Span) -> Self {
+        Self {
+            start: Position {
+                line: span.line_start,
+                column: span.column_start,
+            },
+            end: Some(Position {
+                line: span.line_end,
+                column: span.column_end,
+            }),
+        }
+    }
+}
+
+/// An expected diagnostic, as declared via compiletest-style `//~ <LEVEL> <text>`
+/// comments within test files, where `<text>` is either the diagnostic's code
+/// or a part of its message.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub(crate) struct Annotation {
+    // The (1-based) line the diagnostic is expected on.
+    pub line: usize,
+    pub level: AnnotationLevel,
+    // The expected range of the diagnostic's primary span (if any).
+    pub span: Option<SpanRange>,
+    pub text: String,
+}
+
+impl Annotation {
+    fn matches(&self, diagnostic: &Diagnostic, line: usize, span: Option<&Span>) -> bool {
+        self.line == line
+            && self.level.matches(&diagnostic.level)
+            && (diagnostic.code.as_deref() == Some(self.text.as_str())
+                || diagnostic.message.contains(&self.text))
+            && match (&self.span, span) {
+                (None, _) => true,
+                (Some(range), Some(span)) => range.matches(span),
+                (Some(_), None) => false,
+            }
+    }
+}
+
+impl fmt::Display for Annotation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.span {
+            Some(span) => write!(f, "{}: {}[{}] {}", self.line, self.level, span, self.text),
+            None => write!(f, "{}: {} {}", self.line, self.level, self.text),
+        }
+    }
+}
+
+/// Parses the annotations of a test file's source, where `//~` refers to its own line,
+/// `//~^` (with one `^` per line) to a preceding line and `//~|` to the same line
+/// as the preceding annotation.
+///
+/// Only line comments starting with `//~` are annotations, while any other mention
+/// of `//~` (e.g. within string literals or other comments) gets ignored.
+///
+/// Annotations without an explicit span range get one from the `/*^*/` markers
+/// (if any) on the annotated line: a single marker right before the start
+/// of the expected span, or a pair of them enclosing it.
+pub(crate) fn parse_annotations(source: &str) -> Result<Vec<Annotation>> {
+    let mut annotations: Vec<Annotation> = vec![];
+
+    for (line, annotation) in annotation_comments(source) {
+        let annotation =
+            parse_annotation(annotation, line, annotations.last()).ok_or_else(|| {
+                Error::UnrecognizedAnnotation {
+                    annotation: format!("{ANNOTATION_PREFIX}{annotation}"),
+                }
+            })?;
+
+        annotations.push(annotation);
+    }
+
+    for (index, line) in source.lines().enumerate() {
+        let Some(span) = parse_span_markers(line, index + 1)? else {
+            continue;
+        };
+
+        let mut is_annotated = false;
+        for annotation in &mut annotations {
+            if annotation.line == span.start.line {
+                is_annotated = true;
+                annotation.span.get_or_insert(span);
+            }
+        }
+
+        if !is_annotated {
+            return Err(Error::UnannotatedSpanMarker { line: index + 1 });
+        }
+    }
+
+    Ok(annotations)
+}
+
+/// Returns `true` if the source contains any (well-formed) annotations,
+/// ignoring anything else that merely happens to contain `//~`.
+pub(crate) fn contains_annotations(source: &str) -> bool {
+    annotation_comments(source)
+        .into_iter()
+        .any(|(line, annotation)| parse_annotation(annotation, line, None).is_some())
+}
+
+// Returns the (1-based) lines and contents (following their `//~` prefix) of the source's
+// line comments that start with `//~`, skipping over literals and block comments, so that
+// mentions of `//~` within them don't get mistaken for annotations.
+fn annotation_comments(source: &str) -> Vec<(usize, &str)> {
+    let bytes = source.as_bytes();
+    let is_identifier = |byte: u8| byte.is_ascii_alphanumeric() || byte == b'_';
+
+    let mut comments = vec![];
+    let mut index = 0;
+
+    // All delimiters are ASCII, so we can scan the bytes without splitting any chars:
+    while index < bytes.len() {
+        let rest = &bytes[index..];
+        index += match rest {
+            [b'/', b'/', ..] => {
+                let length = rest
+                    .iter()
+                    .position(|&byte| byte == b'\n')
+                    .unwrap_or(rest.len());
+                if let Some(annotation) =
+                    source[index..index + length].strip_prefix(ANNOTATION_PREFIX)
+                {
+                    let line = bytes[..index].iter().filter(|&&byte| byte == b'\n').count();
+                    comments.push((line + 1, annotation));
+                }
+                length
+            }
+            [b'/', b'*', ..] => block_comment_length(rest),
+            [b'"', ..] => string_literal_length(rest),
+            [b'\'', ..] => char_literal_length(rest),
+            // Raw string literals (e.g. `r#"…"#`) start with an identifier-like prefix:
+            _ if index == 0 || !is_identifier(bytes[index - 1]) => {
+                raw_string_literal_length(rest).unwrap_or(1)
+            }
+            _ => 1,
+        };
+    }
+
+    comments
+}
+
+// Block comments may be nested (e.g. `/* /* … */ */`):
+fn block_comment_length(rest: &[u8]) -> usize {
+    let mut depth = 0;
+    let mut index = 0;
+
+    while index < rest.len() {
+        match rest[index..] {
+            [b'/', b'*', ..] => {
+                depth += 1;
+                index += 2;
+            }
+            [b'*', b'/', ..] => {
+                depth -= 1;
+                index += 2;
+                if depth == 0 {
+                    return index;
+                }
+            }
+            _ => index += 1,
+        }
+    }
+
+    rest.len()
+}
+
+fn string_literal_length(rest: &[u8]) -> usize {
+    let mut index = 1;
+
+    while index < rest.len() {
+        match rest[index] {
+            b'\\' => index += 2,
+            b'"' => return index + 1,
+            _ => index += 1,
+        }
+    }
+
+    rest.len()
+}
+
+// Quotes also start lifetimes and labels (e.g. `'a`), which don't get closed:
+fn char_literal_length(rest: &[u8]) -> usize {
+    if let [b'\'', b'\\', _, ..] = rest {
+        return rest[3..]
+            .iter()
+            .position(|&byte| byte == b'\'')
+            .map_or(rest.len(), |position| position + 4);
+    }
+
+    let width = match rest.get(1) {
+        Some(byte) if *byte < 0x80 => 1,
+        Some(byte) if *byte < 0xE0 => 2,
+        Some(byte) if *byte < 0xF0 => 3,
+        Some(_) => 4,
+        None => return rest.len(),
+    };
+
+    match rest.get(1 + width) {
+        Some(b'\'') => width + 2,
+        _ => 1,
+    }
+}
+
+fn raw_string_literal_length(rest: &[u8]) -> Option<usize> {
+    let prefix = match rest {
+        [b'b' | b'c', b'r', ..] => 2,
+        [b'r', ..] => 1,
+        _ => return None,
+    };
+
+    let hashes = rest[prefix..]
+        .iter()
+        .take_while(|&&byte| byte == b'#')
+        .count();
+    let start = prefix + hashes + 1;
+    if rest.get(start - 1) != Some(&b'"') {
+        return None;
+    }
+
+    let terminator = [&b"\""[..], &rest[prefix..prefix + hashes]].concat();
+    let length = rest[start..]
+        .windows(terminator.len())
+        .position(|window| window == terminator)
+        .map_or(rest.len(), |position| start + position + terminator.len());
+
+    Some(length)
+}
+
+// Parses an annotation (following its `//~` prefix) on the given (1-based) line:
+fn parse_annotation(
+    annotation: &str,
+    line_number: usize,
+    previous: Option<&Annotation>,
+) -> Option<Annotation> {
+    let (line, annotation) = if let Some(annotation) = annotation.strip_prefix('|') {
+        (previous?.line, annotation)
+    } else {
+        let rest = annotation.trim_start_matches('^');
+        let offset = annotation.len() - rest.len();
+        (line_number.checked_sub(offset)?, rest)
+    };
+
+    let (level, text) = annotation.trim().split_once(char::is_whitespace)?;
+    let (level, span) = match level.split_once('[') {
+        Some((level, range)) => {
+            let range = range.strip_suffix(']')?;
+            (level, Some(SpanRange::parse(range, line)?))
+        }
+        None => (level, None),
+    };
+    let level = AnnotationLevel::parse(level)?;
+
+    Some(Annotation {
+        line,
+        level,
+        span,
+        text: text.trim().to_owned(),
+    })
+}
+
+fn parse_span_markers(line: &str, line_number: usize) -> Result<Option<SpanRange>> {
+    // Rustc counts columns in chars, with the markers themselves being part of the line:
+    let column = |byte_index: usize| Position {
+        line: line_number,
+        column: line[..byte_index].chars().count() + 1,
+    };
+
+    let markers: Vec<usize> = line
+        .match_indices(SPAN_MARKER)
+        .map(|(index, _)| index)
+        .collect();
+
+    match markers[..] {
+        [] => Ok(None),
+        [start] => Ok(Some(SpanRange {
+            start: column(start + SPAN_MARKER.len()),
+            end: None,
+        })),
+        [start, end] => Ok(Some(SpanRange {
+            start: column(start + SPAN_MARKER.len()),
+            end: Some(column(end)),
+        })),
+        _ => Err(Error::UnrecognizedAnnotation {
+            annotation: line.trim().to_owned(),
+        }),
+    }
+}
+
+/// The discrepancies between a test file's annotations and its actual diagnostics.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub(crate) struct AnnotationMismatch {
+    // Annotations that didn't match any diagnostic.
+    pub unmatched: Vec<String>,
+    // Errors (or warnings, if any got annotated) that didn't match any annotation.
+    pub unexpected: Vec<String>,
+}
+
+/// Verifies the annotations against the diagnostics reported for the test file,
+/// as identified by its (normalized) `file` name.
+///
+/// Help and note annotations get matched against the diagnostics' children,
+/// which refer to the line of their parent, unless they have a span of their own.
+/// Span ranges get matched against the diagnostics' primary spans.
+pub(crate) fn verify(
+    annotations: &[Annotation],
+    diagnostics: &[Diagnostic],
+    file: &str,
+) -> Option<AnnotationMismatch> {
+    if annotations.is_empty() {
+        return None;
+    }
+
+    let mut actual: Vec<(&Diagnostic, usize, Option<&Span>)> = vec![];
+    for diagnostic in diagnostics {
+        let Some(span) = primary_span(diagnostic, file) else {
+            continue;
+        };
+        actual.push((diagnostic, span.line_start, Some(span)));
+        for child in &diagnostic.children {
+            if !child.spans.iter().any(|span| span.is_primary) {
+                actual.push((child, span.line_start, None));
+            } else if let Some(span) = primary_span(child, file) {
+                actual.push((child, span.line_start, Some(span)));
+            }
+        }
+    }
+
+    let mut matched = vec![false; actual.len()];
+    let mut unmatched = vec![];
+
+    for annotation in annotations {
+        let index = actual
+            .iter()
+            .enumerate()
+            .position(|(index, (diagnostic, line, span))| {
+                !matched[index] && annotation.matches(diagnostic, *line, *span)
+            });
+        match index {
+            Some(index) => matched[index] = true,
+            None => unmatched.push(annotation.to_string()),
+        }
+    }
+
+    // Like compiletest we require all errors to be annotated,
+    // but warnings only if any of them got annotated:
+    let expects_warnings = annotations
+        .iter()
+        .any(|annotation| annotation.level == AnnotationLevel::Warning);
+
+    let unexpected: Vec<String> = actual
+        .iter()
+        .zip(matched)
+        .filter(|((diagnostic, _, _), matched)| {
+            !matched
+                && (diagnostic.is_error()
+                    || (expects_warnings && diagnostic.level == DiagnosticLevel::Warning))
+        })
+        .map(|((diagnostic, line, span), _)| {
+            let level = match diagnostic.is_error() {
+                true => AnnotationLevel::Error,
+                false => AnnotationLevel::Warning,
+            };
+            // We report the actual span range in the annotations' own syntax:
+            let level = match span {
+                Some(span) => format!("{level}[{}]", SpanRange::from(*span)),
+                None => level.to_string(),
+            };
+            match &diagnostic.code {
+                Some(code) => format!("{line}: {level} {code}: {}", diagnostic.message),
+                None => format!("{line}: {level} {}", diagnostic.message),
+            }
+        })
+        .collect();
+
+    if unmatched.is_empty() && unexpected.is_empty() {
+        None
+    } else {
+        Some(AnnotationMismatch {
+            unmatched,
+            unexpected,
+        })
+    }
+}
+
+fn primary_span<'a>(diagnostic: &'a Diagnostic, file: &str) -> Option<&'a Span> {
+    diagnostic
+        .spans
+        .iter()
+        .find(|span| span.is_primary && span.file == file)
+}
@@ -1,157 +1,426 @@
-use std::{borrow::Cow, env, ffi::OsString, io::BufRead, process::Command};
+use std::{borrow::Cow, env, io::BufRead, process::Command};
 
-use serde::Serialize;
+use cargo_metadata::diagnostic::DiagnosticLevel;
 
 use crate::{
+    config::Config,
+    diagnostic::{self, Diagnostic},
     error::{Error, Result},
     normalization,
-    options::Options,
+    options::{Options, TargetKind, Warnings},
     project::Project,
     test::{Test, TestStatus},
     utils::should_debug_log,
 };
 
 const RUSTFLAGS_ENV_KEY: &str = "RUSTFLAGS";
+const ENCODED_RUSTFLAGS_ENV_KEY: &str = "CARGO_ENCODED_RUSTFLAGS";
+const RUSTDOCFLAGS_ENV_KEY: &str = "RUSTDOCFLAGS";
+const ENCODED_RUSTDOCFLAGS_ENV_KEY: &str = "CARGO_ENCODED_RUSTDOCFLAGS";
+
+/// The separator of flags within `CARGO_ENCODED_RUSTFLAGS`/`CARGO_ENCODED_RUSTDOCFLAGS`.
+const ENCODED_FLAGS_SEPARATOR: char = '\x1f';
 
 fn raw_cargo() -> Command {
     Command::new(option_env!("CARGO").unwrap_or("cargo"))
 }
 
-fn cargo(project: &Project) -> Command {
+fn cargo(project: &Project, options: &Options) -> Command {
     let mut cmd = raw_cargo();
     cmd.current_dir(&project.dir);
     cmd.env("CARGO_TARGET_DIR", &project.target_dir);
-    cmd.env(RUSTFLAGS_ENV_KEY, make_rustflags_env());
+    if let Some(rustflags) = make_flags_env(
+        ENCODED_RUSTFLAGS_ENV_KEY,
+        RUSTFLAGS_ENV_KEY,
+        tryexpand_rustflags(options),
+        options,
+    ) {
+        cmd.env(ENCODED_RUSTFLAGS_ENV_KEY, rustflags);
+    }
+    if let Some(rustdocflags) = make_flags_env(
+        ENCODED_RUSTDOCFLAGS_ENV_KEY,
+        RUSTDOCFLAGS_ENV_KEY,
+        tryexpand_rustdocflags(options),
+        options,
+    ) {
+        cmd.env(ENCODED_RUSTDOCFLAGS_ENV_KEY, rustdocflags);
+    }
     cmd
 }
 
-#[derive(Serialize, Debug)]
-pub struct Config {
-    pub build: Build,
+/// Returns the source project's cargo config, extended by tryexpand's own settings.
+pub(crate) fn make_config(project: &Project, options: &Options) -> Result<Config> {
+    let mut config = Config::load(&project.manifest_dir, &project.dir)?;
+    config.append_flags("rustflags", tryexpand_rustflags(options));
+    config.append_flags("rustdocflags", tryexpand_rustdocflags(options));
+    Ok(config)
 }
 
-#[derive(Serialize, Debug)]
-pub struct Build {
-    pub rustflags: Vec<String>,
-}
+fn tryexpand_rustflags(options: &Options) -> Vec<String> {
+    let mut rustflags = cfg_flags(options);
+
+    rustflags.extend(options.rustflags.iter().cloned());
 
-pub(crate) fn make_config() -> Config {
-    Config {
-        build: Build {
-            rustflags: tryexpand_rustflags(),
-        },
+    match options.warnings {
+        Warnings::Suppress => rustflags.push("-Awarnings".to_owned()),
+        Warnings::Report | Warnings::Deny => {}
     }
+
+    rustflags
+}
+
+fn tryexpand_rustdocflags(options: &Options) -> Vec<String> {
+    cfg_flags(options)
 }
 
-fn tryexpand_rustflags() -> Vec<String> {
-    vec!["-Awarnings".to_owned()]
+fn cfg_flags(options: &Options) -> Vec<String> {
+    let mut flags = vec![];
+
+    for (name, value) in &options.cfgs {
+        let (cfg, check_cfg) = match value {
+            Some(value) => (
+                format!("{name}={value:?}"),
+                format!("cfg({name}, values({value:?}))"),
+            ),
+            None => (name.clone(), format!("cfg({name})")),
+        };
+        flags.extend(["--cfg".to_owned(), cfg, "--check-cfg".to_owned(), check_cfg]);
+    }
+
+    flags
 }
 
-fn make_rustflags_env() -> OsString {
-    let mut rustflags = match env::var_os(RUSTFLAGS_ENV_KEY) {
-        Some(rustflags) => rustflags,
-        None => OsString::new(),
+/// Returns the flags provided via the (test suite's or process') environment, if any,
+/// extended by `flags` and encoded for passing via `encoded_key`.
+///
+/// Cargo ignores the flags of its config if any are provided via the environment,
+/// so we only need to pass `flags` via the environment in that case.
+fn make_flags_env(
+    encoded_key: &str,
+    key: &str,
+    flags: Vec<String>,
+    options: &Options,
+) -> Option<String> {
+    let var = |key: &str| {
+        options
+            .envs
+            .get(key)
+            .cloned()
+            .or_else(|| env::var(key).ok())
     };
 
-    for rustflag in tryexpand_rustflags() {
-        rustflags.push(rustflag);
+    let mut env_flags: Vec<String> = if let Some(encoded) = var(encoded_key) {
+        encoded
+            .split(ENCODED_FLAGS_SEPARATOR)
+            .filter(|flag| !flag.is_empty())
+            .map(str::to_owned)
+            .collect()
+    } else if let Some(flags) = var(key) {
+        flags.split_whitespace().map(str::to_owned).collect()
+    } else {
+        return None;
+    };
+
+    env_flags.extend(flags);
+
+    Some(env_flags.join(&ENCODED_FLAGS_SEPARATOR.to_string()))
+}
+
+fn target_args<'a>(test: &'a Test, options: &Options) -> Vec<&'a str> {
+    match options.target_kind {
+        TargetKind::Bin => vec!["--bin", &test.bin],
+        TargetKind::Lib => vec!["--package", &test.bin, "--lib"],
     }
+}
 
-    rustflags
+fn lockfile_args(options: &Options) -> Vec<&'static str> {
+    let mut args = vec![];
+
+    if options.locked {
+        args.push("--locked");
+    }
+
+    if options.offline {
+        args.push("--offline");
+    }
+
+    args
+}
+
+fn profile_args(options: &Options) -> Vec<&'static str> {
+    let mut args = vec![];
+
+    if options.release {
+        args.push("--release");
+    }
+
+    args
+}
+
+fn feature_args(options: &Options) -> Vec<String> {
+    let mut args = vec![];
+
+    if !options.features.is_empty() {
+        args.push("--features".to_owned());
+        args.push(options.features.join(","));
+    }
+
+    if options.all_features {
+        args.push("--all-features".to_owned());
+    }
+
+    if options.no_default_features {
+        args.push("--no-default-features".to_owned());
+    }
+
+    args
 }
 
 #[derive(Clone, Eq, PartialEq, Debug)]
 pub(crate) struct CargoOutput {
     pub stdout: Option<String>,
     pub stderr: Option<String>,
+    // The structured compiler diagnostics (for commands that provide them).
+    pub diagnostics: Vec<Diagnostic>,
     pub evaluation: TestStatus,
 }
 
 pub(crate) fn expand(project: &Project, test: &Test, options: &Options) -> Result<CargoOutput> {
-    let mut cargo = cargo(project);
+    let mut cargo = cargo(project, options);
 
     cargo
         .arg("expand")
-        .arg("--bin")
-        .arg(&test.bin)
+        .args(target_args(test, options))
         .arg("--theme")
         .arg("none");
 
     let CargoOutput {
         stdout,
+        stderr: expand_stderr,
+        evaluation: expand_evaluation,
+        ..
+    } = run_cargo_command(cargo, options)?;
+
+    let CargoOutput {
         stderr,
+        diagnostics,
         evaluation,
-    } = run_cargo_command(cargo, options)?;
+        ..
+    } = extract_diagnostics(expansion_diagnostics(project, test, options)?);
+
+    // Failures of `cargo expand` itself (e.g. if it isn't installed)
+    // don't show up in the expansion's diagnostics:
+    let (stderr, evaluation) = match (expand_evaluation, evaluation) {
+        (TestStatus::Failure, TestStatus::Success) => (expand_stderr, TestStatus::Failure),
+        _ => (stderr, evaluation),
+    };
 
     let stdout = stdout.and_then(|stdout| {
         normalization::expand_stdout(Cow::from(stdout), project, test, &options.filters)
             .map(|cow| cow.into_owned())
     });
     let stderr = stderr.and_then(|stderr| {
-        normalization::expand_stderr(Cow::from(stderr), project, test, &options.filters)
-            .map(|cow| cow.into_owned())
+        normalization::expand_stderr(
+            Cow::from(stderr),
+            project,
+            test,
+            &options.filters,
+            keeps_warnings(options),
+        )
+        .map(|cow| cow.into_owned())
     });
 
-    let evaluation = match evaluation {
-        TestStatus::Success => {
-            if let Some(stderr) = &stderr {
-                // Unfortunately `cargo expand` will sometimes return a success status,
-                // despite the expansion having produced errors in the log:
-                let found_errors = stderr.lines().any(line_is_error);
-                TestStatus::failure(found_errors)
-            } else {
-                TestStatus::Success
-            }
-        }
-        TestStatus::Failure => evaluation,
-    };
+    let diagnostics = normalization::normalize_diagnostics(diagnostics, project, test);
+
+    let evaluation = evaluate_diagnostic_warnings(evaluation, &diagnostics, project, test, options);
 
     Ok(CargoOutput {
         stdout,
         stderr,
+        diagnostics,
         evaluation,
     })
 }
 
+// Neither does `cargo expand` emit JSON messages, nor does it reliably report failures
+// (as it sometimes returns success despite the expansion having produced errors),
+// so we run its underlying compiler invocation ourselves to obtain its diagnostics:
+fn expansion_diagnostics(project: &Project, test: &Test, options: &Options) -> Result<CargoOutput> {
+    let mut cargo = cargo(project, options);
+
+    // Just like `cargo expand` we need unstable flags, even on stable toolchains:
+    cargo
+        .env("RUSTC_BOOTSTRAP", "1")
+        .arg("rustc")
+        .args(target_args(test, options))
+        .arg("--message-format=json")
+        .arg("--color")
+        .arg("never");
+
+    // Release builds are selected via `--release` (which conflicts with `--profile`):
+    if !options.release {
+        cargo.arg("--profile=check");
+    }
+
+    run_cargo_command_with_trailing_args(cargo, options, ["-Zunpretty=expanded"])
+}
+
 pub(crate) fn check(project: &Project, test: &Test, options: &Options) -> Result<CargoOutput> {
-    let mut cargo = cargo(project);
+    let mut cargo = cargo(project, options);
 
     cargo
         .arg("check")
-        .arg("--bin")
-        .arg(&test.bin)
+        .args(target_args(test, options))
+        .arg("--message-format=json")
         .arg("--color")
         .arg("never");
 
     let CargoOutput {
+        stderr,
+        diagnostics,
+        evaluation,
+        ..
+    } = extract_diagnostics(run_cargo_command(cargo, options)?);
+
+    // Cargo reports the `cargo:warning=…` output of build scripts on stderr,
+    // so that's where we extract it from, rather than from stdout:
+    let stdout = stderr.as_deref().and_then(|stderr| {
+        normalization::check_stdout(Cow::from(stderr), project, test, &options.filters)
+            .map(|cow| cow.into_owned())
+    });
+    let stderr = stderr.and_then(|stderr| {
+        normalization::check_stderr(
+            Cow::from(stderr),
+            project,
+            test,
+            &options.filters,
+            keeps_warnings(options),
+        )
+        .map(|cow| cow.into_owned())
+    });
+
+    let diagnostics = normalization::normalize_diagnostics(diagnostics, project, test);
+
+    let evaluation = evaluate_diagnostic_warnings(evaluation, &diagnostics, project, test, options);
+
+    Ok(CargoOutput {
         stdout,
         stderr,
+        diagnostics,
         evaluation,
-    } = run_cargo_command(cargo, options)?;
+    })
+}
+
+pub(crate) fn clippy(project: &Project, test: &Test, options: &Options) -> Result<CargoOutput> {
+    let mut cargo = cargo(project, options);
+
+    cargo
+        .arg("clippy")
+        .args(target_args(test, options))
+        .arg("--message-format=json")
+        .arg("--color")
+        .arg("never");
+
+    // Lints are suppressed via `-Awarnings` by default, so we need to re-enable them
+    // (for the test file's crate only) before applying the user's lint levels:
+    let clippy_args =
+        std::iter::once("-Wwarnings").chain(options.clippy_lints.iter().map(String::as_str));
+
+    let CargoOutput {
+        stdout,
+        stderr,
+        diagnostics,
+        evaluation,
+    } = extract_diagnostics(run_cargo_command_with_trailing_args(
+        cargo,
+        options,
+        clippy_args,
+    )?);
+
+    let stdout = stdout.and_then(|stdout| {
+        normalization::clippy_stdout(Cow::from(stdout), project, test, &options.filters)
+            .map(|cow| cow.into_owned())
+    });
+    let stderr = stderr.and_then(|stderr| {
+        normalization::clippy_stderr(Cow::from(stderr), project, test, &options.filters)
+            .map(|cow| cow.into_owned())
+    });
+
+    let diagnostics = normalization::normalize_diagnostics(diagnostics, project, test);
+
+    let evaluation = evaluate_diagnostic_warnings(evaluation, &diagnostics, project, test, options);
+
+    Ok(CargoOutput {
+        stdout,
+        stderr,
+        diagnostics,
+        evaluation,
+    })
+}
+
+pub(crate) fn doc(project: &Project, test: &Test, options: &Options) -> Result<CargoOutput> {
+    let mut cargo = cargo(project, options);
+
+    cargo
+        .arg("doc")
+        .args(target_args(test, options))
+        .arg("--no-deps")
+        .arg("--message-format=json")
+        .arg("--color")
+        .arg("never");
+
+    let CargoOutput {
+        stdout,
+        stderr,
+        diagnostics,
+        evaluation,
+    } = extract_diagnostics(run_cargo_command(cargo, options)?);
 
     let stdout = stdout.and_then(|stdout| {
-        normalization::check_stdout(Cow::from(stdout), project, test, &options.filters)
+        normalization::doc_stdout(Cow::from(stdout), project, test, &options.filters)
             .map(|cow| cow.into_owned())
     });
     let stderr = stderr.and_then(|stderr| {
-        normalization::check_stderr(Cow::from(stderr), project, test, &options.filters)
+        normalization::doc_stderr(Cow::from(stderr), project, test, &options.filters)
             .map(|cow| cow.into_owned())
     });
 
+    let diagnostics = normalization::normalize_diagnostics(diagnostics, project, test);
+
+    let evaluation = evaluate_diagnostic_warnings(evaluation, &diagnostics, project, test, options);
+
     Ok(CargoOutput {
         stdout,
         stderr,
+        diagnostics,
         evaluation,
     })
 }
 
 pub(crate) fn test(project: &Project, test: &Test, options: &Options) -> Result<CargoOutput> {
-    let mut cargo = cargo(project);
+    run_tests(project, test, options, target_args(test, options))
+}
+
+pub(crate) fn doc_test(project: &Project, test: &Test, options: &Options) -> Result<CargoOutput> {
+    run_tests(
+        project,
+        test,
+        options,
+        vec!["--package", &test.bin, "--doc"],
+    )
+}
+
+fn run_tests(
+    project: &Project,
+    test: &Test,
+    options: &Options,
+    target_args: Vec<&str>,
+) -> Result<CargoOutput> {
+    let mut cargo = cargo(project, options);
 
     cargo
         .arg("test")
-        .arg("--bin")
-        .arg(&test.bin)
+        .args(target_args)
         .arg("--color")
         .arg("never")
         .arg("--quiet");
@@ -163,6 +432,7 @@ pub(crate) fn test(project: &Project, test: &Test, options: &Options) -> Result<
         stdout,
         stderr,
         evaluation,
+        ..
     } = run_cargo_command(cargo, options)?;
 
     let stdout = stdout.and_then(|stdout| {
@@ -190,20 +460,22 @@ pub(crate) fn test(project: &Project, test: &Test, options: &Options) -> Result<
         TestStatus::Failure => evaluation,
     };
 
+    let evaluation = evaluate_warnings(evaluation, stderr.as_deref(), project, test, options);
+
     Ok(CargoOutput {
         stdout,
         stderr,
+        diagnostics: vec![],
         evaluation,
     })
 }
 
 pub(crate) fn run(project: &Project, test: &Test, options: &Options) -> Result<CargoOutput> {
-    let mut cargo = cargo(project);
+    let mut cargo = cargo(project, options);
 
     cargo
         .arg("run")
-        .arg("--bin")
-        .arg(&test.bin)
+        .args(target_args(test, options))
         .arg("--color")
         .arg("never")
         .arg("--quiet");
@@ -215,6 +487,7 @@ pub(crate) fn run(project: &Project, test: &Test, options: &Options) -> Result<C
         stdout,
         stderr,
         evaluation,
+        ..
     } = run_cargo_command(cargo, options)?;
 
     let stdout = stdout.and_then(|stdout| {
@@ -226,17 +499,158 @@ pub(crate) fn run(project: &Project, test: &Test, options: &Options) -> Result<C
             .map(|cow| cow.into_owned())
     });
 
+    let evaluation = evaluate_warnings(evaluation, stderr.as_deref(), project, test, options);
+
     Ok(CargoOutput {
         stdout,
         stderr,
+        diagnostics: vec![],
         evaluation,
     })
 }
 
-fn run_cargo_command(mut cargo: Command, options: &Options) -> Result<CargoOutput> {
+fn keeps_warnings(options: &Options) -> bool {
+    options.warnings != Warnings::Suppress
+}
+
+// Takes the compiler's diagnostics from the (stdout) output of a cargo command
+// that got invoked with `--message-format=json` and prepends their human-readable
+// renderings to cargo's own stderr, where they would have been reported otherwise.
+fn extract_diagnostics(output: CargoOutput) -> CargoOutput {
+    let CargoOutput {
+        stdout,
+        stderr,
+        evaluation,
+        ..
+    } = output;
+
+    let diagnostics = diagnostic::parse(stdout.as_deref().unwrap_or_default());
+
+    let stderr = stderr.map(|stderr| diagnostic::render(&diagnostics) + &stderr);
+
+    // Cargo may also fail without reporting any errors from the compiler
+    // (e.g. due to a failing build script), so we only ever turn success into failure:
+    let evaluation = if diagnostics.iter().any(Diagnostic::is_error) {
+        TestStatus::Failure
+    } else {
+        evaluation
+    };
+
+    CargoOutput {
+        stdout: None,
+        stderr,
+        diagnostics,
+        evaluation,
+    }
+}
+
+// Turns a successful evaluation into a failure if warnings are denied
+// and the compiler reported any warnings within the test's own files
+// (rather than e.g. within the crate under test, or auxiliary crates):
+fn evaluate_diagnostic_warnings(
+    evaluation: TestStatus,
+    diagnostics: &[Diagnostic],
+    project: &Project,
+    test: &Test,
+    options: &Options,
+) -> TestStatus {
+    if options.warnings != Warnings::Deny {
+        return evaluation;
+    }
+
+    let test_path = normalization::diagnostics_test_path(project, test);
+
+    let found_warnings = diagnostics.iter().any(|diagnostic| {
+        diagnostic.level == DiagnosticLevel::Warning
+            && diagnostic
+                .spans
+                .iter()
+                .any(|span| span.is_primary && normalization::is_test_file(&span.file, &test_path))
+    });
+
+    evaluation & TestStatus::failure(found_warnings)
+}
+
+// Like `evaluate_diagnostic_warnings()`, but for commands that don't provide
+// structured diagnostics, where we go by the (normalized) `--> <file>:<line>:<column>`
+// location that follows a warning's message:
+fn evaluate_warnings(
+    evaluation: TestStatus,
+    stderr: Option<&str>,
+    project: &Project,
+    test: &Test,
+    options: &Options,
+) -> TestStatus {
+    if options.warnings != Warnings::Deny {
+        return evaluation;
+    }
+
+    let test_path = normalization::diagnostics_test_path(project, test);
+
+    let mut is_within_warning = false;
+    let mut found_warnings = false;
+
+    for line in stderr.unwrap_or_default().lines() {
+        if line_is_warning(line) {
+            is_within_warning = true;
+        } else if line_is_error(line) {
+            is_within_warning = false;
+        } else if let Some(location) = line.trim_start().strip_prefix("--> ") {
+            // Only the first location refers to the warning's primary span:
+            if is_within_warning {
+                let file = location.rsplitn(3, ':').nth(2).unwrap_or(location);
+                found_warnings |= normalization::is_test_file(file, &test_path);
+            }
+            is_within_warning = false;
+        }
+    }
+
+    evaluation & TestStatus::failure(found_warnings)
+}
+
+fn run_cargo_command(cargo: Command, options: &Options) -> Result<CargoOutput> {
+    run_cargo_command_with_trailing_args(cargo, options, Vec::<String>::new())
+}
+
+// Trailing arguments get passed after `--`, i.e. to the tool invoked by cargo (if any):
+fn run_cargo_command_with_trailing_args<I, S>(
+    mut cargo: Command,
+    options: &Options,
+    trailing_args: I,
+) -> Result<CargoOutput>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<std::ffi::OsStr>,
+{
+    cargo.args(lockfile_args(options));
+    cargo.args(feature_args(options));
+    cargo.args(profile_args(options));
     cargo.args(&options.args);
 
+    let mut trailing_args = trailing_args.into_iter().peekable();
+    if trailing_args.peek().is_some() {
+        cargo.arg("--").args(trailing_args);
+    }
+
+    execute_cargo_command(cargo, options)
+}
+
+fn is_flags_env_key(key: &str) -> bool {
+    [
+        RUSTFLAGS_ENV_KEY,
+        ENCODED_RUSTFLAGS_ENV_KEY,
+        RUSTDOCFLAGS_ENV_KEY,
+        ENCODED_RUSTDOCFLAGS_ENV_KEY,
+    ]
+    .contains(&key)
+}
+
+fn execute_cargo_command(mut cargo: Command, options: &Options) -> Result<CargoOutput> {
     for (key, value) in &options.envs {
+        // Flags get merged with tryexpand's own flags by `cargo()`:
+        if is_flags_env_key(key) {
+            continue;
+        }
         cargo.env(key, value);
     }
 
@@ -269,6 +683,7 @@ fn run_cargo_command(mut cargo: Command, options: &Options) -> Result<CargoOutpu
     Ok(CargoOutput {
         stdout,
         stderr,
+        diagnostics: vec![],
         evaluation,
     })
 }
@@ -277,7 +692,7 @@ fn run_cargo_command(mut cargo: Command, options: &Options) -> Result<CargoOutpu
 /// Tries to expand macros in `main.rs` and intentionally filters the result.
 /// This function is called before macro expansions to speed them up and
 /// for dependencies build process to be visible for user.
-pub(crate) fn build_dependencies(project: &Project) -> Result<()> {
+pub(crate) fn build_dependencies(project: &Project, options: &Options) -> Result<()> {
     use std::io::Write;
 
     const IGNORED_LINES: [&str; 5] = [
@@ -301,11 +716,14 @@ pub(crate) fn build_dependencies(project: &Project) -> Result<()> {
     let _ = writeln!(std::io::stdout());
     let _ = writeln!(std::io::stdout());
 
-    let stdout = cargo(project)
+    let stdout = cargo(project, options)
         .arg("check")
         .arg("--lib")
         .arg("--color")
         .arg("never")
+        .args(lockfile_args(options))
+        .args(feature_args(options))
+        .args(profile_args(options))
         .stdout(std::process::Stdio::piped())
         .spawn()
         .map_err(Error::SpawningProcessFailed)?
@@ -326,6 +744,32 @@ pub(crate) fn build_dependencies(project: &Project) -> Result<()> {
     Ok(())
 }
 
+/// Adds the test project's own package to the lockfile that got seeded
+/// from the source project, while keeping all other packages pinned.
+///
+/// This is necessary for subsequent `--locked` commands to succeed.
+pub(crate) fn sync_lockfile(project: &Project, options: &Options) -> Result<()> {
+    let mut cargo = cargo(project, options);
+
+    cargo.arg("update").arg("--workspace").arg("--quiet");
+
+    if options.offline {
+        cargo.arg("--offline");
+    }
+
+    let output = cargo.output().map_err(Error::SpawningProcessFailed)?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+        return Err(Error::CargoLockfileSync {
+            path: project.dir.join("Cargo.lock"),
+            stderr,
+        });
+    }
+
+    Ok(())
+}
+
 pub(crate) fn line_should_be_omitted(line: &str) -> bool {
     let line = line.trim();
 
@@ -346,11 +790,31 @@ pub(crate) fn line_is_warning(line: &str) -> bool {
     false
 }
 
+pub(crate) fn line_is_status(line: &str) -> bool {
+    const STATUSES: [&str; 8] = [
+        "Blocking",
+        "Checking",
+        "Compiling",
+        "Documenting",
+        "Finished",
+        "Fresh",
+        "Generated",
+        "Running",
+    ];
+
+    // Cargo's status lines (e.g. `    Finished `dev` profile [...] in 0.42s`)
+    // may end up interleaved with warnings, but aren't of interest to us:
+    let line = line.trim_start();
+    STATUSES.iter().any(|status| {
+        line.strip_prefix(status)
+            .is_some_and(|rest| rest.starts_with(' '))
+    })
+}
+
 pub(crate) fn line_is_error(line: &str) -> bool {
     let line = line.trim();
 
-    // Sometimes the `cargo expand` command returns a success status,
-    // despite an error having occurred, so we need to look for those:
+    // Errors mark the start of the output that's of interest to us:
 
     // Example:
     // ```
@@ -0,0 +1,271 @@
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
+
+use serde::{ser::SerializeMap, Serialize, Serializer};
+use serde_json::{Map, Value};
+
+use crate::{
+    error::{Error, Result},
+    utils,
+};
+
+/// The names of the files within a `.cargo` directory that cargo reads
+/// its configuration from, in order of precedence.
+const CONFIG_FILE_NAMES: [&str; 2] = ["config", "config.toml"];
+
+/// A cargo configuration, as found in `.cargo/config.toml` files.
+#[derive(Default, Debug)]
+pub(crate) struct Config(Map<String, Value>);
+
+impl Config {
+    /// Loads and merges the configuration files that cargo would find
+    /// when invoked from within `dir`, except for those files that cargo
+    /// also finds on its own when invoked from within `project_dir`
+    /// (i.e. those of shared ancestor directories and cargo's home).
+    pub(crate) fn load(dir: &Path, project_dir: &Path) -> Result<Self> {
+        let cargo_home = cargo_home();
+
+        let mut config = Self::default();
+
+        // Cargo gives precedence to the configuration files of deeper directories,
+        // so we merge them starting with the outermost one:
+        let dirs: Vec<&Path> = dir.ancestors().collect();
+        for dir in dirs.into_iter().rev() {
+            if project_dir.starts_with(dir) {
+                continue;
+            }
+
+            let cargo_dir = dir.join(".cargo");
+            if cargo_home.as_deref() == Some(cargo_dir.as_path()) {
+                continue;
+            }
+
+            let Some(path) = CONFIG_FILE_NAMES
+                .iter()
+                .map(|file_name| cargo_dir.join(file_name))
+                .find(|path| path.is_file())
+            else {
+                continue;
+            };
+
+            let contents = utils::read(&path)?;
+            let contents = String::from_utf8_lossy(&contents);
+            let mut layer: Map<String, Value> = basic_toml::from_str(&contents)
+                .map_err(|source| Error::CargoConfigParsingFailed { path, source })?;
+
+            absolutize_paths(&mut layer, dir);
+
+            merge(&mut config.0, layer);
+        }
+
+        Ok(config)
+    }
+
+    /// Appends `flags` to the configuration's flags of the given kind
+    /// (i.e. `rustflags` or `rustdocflags`).
+    pub(crate) fn append_flags(&mut self, kind: &str, flags: Vec<String>) {
+        if flags.is_empty() {
+            return;
+        }
+
+        // Cargo ignores `build.<kind>` if there are any matching `target.<…>.<kind>`,
+        // so we need to append to those as well:
+        if let Some(Value::Object(targets)) = self.0.get_mut("target") {
+            for target in targets.values_mut() {
+                if let Value::Object(target) = target {
+                    if let Some(existing) = target.get_mut(kind) {
+                        append_to_flags(existing, &flags);
+                    }
+                }
+            }
+        }
+
+        let build = self
+            .0
+            .entry("build")
+            .or_insert_with(|| Value::Object(Map::new()));
+
+        if let Value::Object(build) = build {
+            let existing = build.entry(kind).or_insert_with(|| Value::Array(vec![]));
+            append_to_flags(existing, &flags);
+        }
+    }
+
+    pub(crate) fn to_toml(&self) -> Result<String> {
+        basic_toml::to_string(&Table(&self.0)).map_err(Error::CargoConfigSerializationFailed)
+    }
+}
+
+fn append_to_flags(existing: &mut Value, flags: &[String]) {
+    // Cargo also accepts flags as a space-separated string:
+    if let Value::String(string) = existing {
+        *existing = string
+            .split_whitespace()
+            .map(|flag| Value::String(flag.to_owned()))
+            .collect();
+    }
+
+    if let Value::Array(existing) = existing {
+        existing.extend(flags.iter().cloned().map(Value::String));
+    }
+}
+
+fn cargo_home() -> Option<PathBuf> {
+    if let Some(cargo_home) = env::var_os("CARGO_HOME") {
+        return Some(PathBuf::from(cargo_home));
+    }
+
+    env::home_dir().map(|home_dir| home_dir.join(".cargo"))
+}
+
+/// Merges `layer` into `config`, the way cargo merges configuration files:
+/// tables get merged recursively, arrays get joined and any other values
+/// get replaced by the ones from `layer`.
+fn merge(config: &mut Map<String, Value>, layer: Map<String, Value>) {
+    for (key, value) in layer {
+        match (config.get_mut(&key), value) {
+            (Some(Value::Object(existing)), Value::Object(table)) => merge(existing, table),
+            (Some(Value::Array(existing)), Value::Array(array)) => existing.extend(array),
+            (_, value) => {
+                config.insert(key, value);
+            }
+        }
+    }
+}
+
+/// Resolves the relative paths within a configuration file against `dir`,
+/// the directory containing the file's `.cargo` directory, so that they
+/// remain valid from within the generated project.
+fn absolutize_paths(layer: &mut Map<String, Value>, dir: &Path) {
+    if let Some(Value::Array(paths)) = layer.get_mut("paths") {
+        for path in paths {
+            absolutize_path(path, dir);
+        }
+    }
+
+    if let Some(Value::Object(build)) = layer.get_mut("build") {
+        absolutize_path_at(build, "target-dir", dir);
+        for key in [
+            "rustc",
+            "rustc-wrapper",
+            "rustc-workspace-wrapper",
+            "rustdoc",
+        ] {
+            absolutize_program_at(build, key, dir);
+        }
+    }
+
+    if let Some(Value::Object(sources)) = layer.get_mut("source") {
+        for source in sources.values_mut() {
+            if let Value::Object(source) = source {
+                absolutize_path_at(source, "directory", dir);
+                absolutize_path_at(source, "local-registry", dir);
+            }
+        }
+    }
+
+    if let Some(Value::Object(env)) = layer.get_mut("env") {
+        for var in env.values_mut() {
+            let Value::Object(var) = var else {
+                continue;
+            };
+            if var.get("relative") == Some(&Value::Bool(true)) {
+                absolutize_path_at(var, "value", dir);
+            }
+        }
+    }
+
+    for key in ["host", "target"] {
+        let Some(Value::Object(table)) = layer.get_mut(key) else {
+            continue;
+        };
+
+        // The `host` table may contain its settings either directly,
+        // or nested within tables for specific target triples:
+        absolutize_program_at(table, "linker", dir);
+        for value in table.values_mut() {
+            if let Value::Object(table) = value {
+                absolutize_program_at(table, "linker", dir);
+                absolutize_program_at(table, "runner", dir);
+            }
+        }
+    }
+}
+
+fn absolutize_path_at(table: &mut Map<String, Value>, key: &str, dir: &Path) {
+    if let Some(path) = table.get_mut(key) {
+        absolutize_path(path, dir);
+    }
+}
+
+fn absolutize_path(path: &mut Value, dir: &Path) {
+    if let Value::String(string) = path {
+        if Path::new(string).is_relative() {
+            *string = dir.join(&*string).to_string_lossy().into_owned();
+        }
+    }
+}
+
+/// Cargo looks up programs without path separators in `PATH`,
+/// so we only resolve those that do contain any.
+fn absolutize_program_at(table: &mut Map<String, Value>, key: &str, dir: &Path) {
+    match table.get_mut(key) {
+        Some(Value::String(string)) => {
+            // Runners may contain their arguments as a space-separated string:
+            let (program, args) = match string.split_once(' ') {
+                Some((program, args)) => (program, Some(args)),
+                None => (string.as_str(), None),
+            };
+            if let Some(program) = absolutize_program(program, dir) {
+                *string = match args {
+                    Some(args) => format!("{program} {args}"),
+                    None => program,
+                };
+            }
+        }
+        Some(Value::Array(array)) => {
+            if let Some(Value::String(program)) = array.first_mut() {
+                if let Some(absolute) = absolutize_program(program, dir) {
+                    *program = absolute;
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn absolutize_program(program: &str, dir: &Path) -> Option<String> {
+    let path = Path::new(program);
+    if path.is_relative() && path.components().count() > 1 {
+        Some(dir.join(path).to_string_lossy().into_owned())
+    } else {
+        None
+    }
+}
+
+/// A serialization wrapper that emits a table's values before its (sub-)tables,
+/// as required by TOML.
+struct Table<'a>(&'a Map<String, Value>);
+
+impl Serialize for Table<'_> {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let (tables, values): (Vec<_>, Vec<_>) = self
+            .0
+            .iter()
+            .partition(|(_, value)| matches!(value, Value::Object(_)));
+
+        let mut map = serializer.serialize_map(Some(self.0.len()))?;
+        for (key, value) in values.into_iter().chain(tables) {
+            match value {
+                Value::Object(table) => map.serialize_entry(key, &Table(table))?,
+                value => map.serialize_entry(key, value)?,
+            }
+        }
+        map.end()
+    }
+}
@@ -0,0 +1,90 @@
+use cargo_metadata::{
+    diagnostic::{Diagnostic as CargoDiagnostic, DiagnosticLevel, DiagnosticSpan as CargoSpan},
+    Message,
+};
+use serde::Serialize;
+
+/// A compiler diagnostic, as reported by cargo via `--message-format=json`.
+#[derive(Clone, Eq, PartialEq, Serialize, Debug)]
+pub(crate) struct Diagnostic {
+    pub level: DiagnosticLevel,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    pub message: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub spans: Vec<Span>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<Diagnostic>,
+    // The diagnostic as it would have been printed by rustc (if at the top-level).
+    #[serde(skip)]
+    pub rendered: Option<String>,
+}
+
+impl Diagnostic {
+    pub(crate) fn is_error(&self) -> bool {
+        matches!(self.level, DiagnosticLevel::Error | DiagnosticLevel::Ice)
+    }
+}
+
+impl From<CargoDiagnostic> for Diagnostic {
+    fn from(diagnostic: CargoDiagnostic) -> Self {
+        Self {
+            level: diagnostic.level,
+            code: diagnostic.code.map(|code| code.code),
+            message: diagnostic.message,
+            spans: diagnostic.spans.into_iter().map(Span::from).collect(),
+            children: diagnostic
+                .children
+                .into_iter()
+                .map(Diagnostic::from)
+                .collect(),
+            rendered: diagnostic.rendered,
+        }
+    }
+}
+
+/// A source code span of a compiler diagnostic (with 1-based lines and columns).
+#[derive(Clone, Eq, PartialEq, Serialize, Debug)]
+pub(crate) struct Span {
+    pub file: String,
+    pub line_start: usize,
+    pub column_start: usize,
+    pub line_end: usize,
+    pub column_end: usize,
+    pub is_primary: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+}
+
+impl From<CargoSpan> for Span {
+    fn from(span: CargoSpan) -> Self {
+        Self {
+            file: span.file_name,
+            line_start: span.line_start,
+            column_start: span.column_start,
+            line_end: span.line_end,
+            column_end: span.column_end,
+            is_primary: span.is_primary,
+            label: span.label,
+        }
+    }
+}
+
+/// Parses the compiler diagnostics from the (stdout) output of a cargo command
+/// that got invoked with `--message-format=json`, skipping any other messages.
+pub(crate) fn parse(output: &str) -> Vec<Diagnostic> {
+    Message::parse_stream(output.as_bytes())
+        .filter_map(|message| match message {
+            Ok(Message::CompilerMessage(message)) => Some(Diagnostic::from(message.message)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Renders the diagnostics the way rustc would have printed them.
+pub(crate) fn render(diagnostics: &[Diagnostic]) -> String {
+    diagnostics
+        .iter()
+        .filter_map(|diagnostic| diagnostic.rendered.as_deref())
+        .collect()
+}
@@ -0,0 +1,41 @@
+use std::path::PathBuf;
+
+use crate::error::{Error, Result};
+
+const DIRECTIVE_PREFIX: &str = "// tryexpand:";
+
+/// A directive, as declared via `// tryexpand: <key> = "<value>"` comments within test files.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub(crate) enum Directive {
+    /// An auxiliary crate (relative to the test file's directory)
+    /// to make available to the test file as a dependency
+    /// (and, unless compiled as library targets, to all other test files of its suite).
+    Aux(PathBuf),
+}
+
+pub(crate) fn parse_directives(source: &str) -> Result<Vec<Directive>> {
+    source
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix(DIRECTIVE_PREFIX))
+        .map(parse_directive)
+        .collect()
+}
+
+fn parse_directive(directive: &str) -> Result<Directive> {
+    let unrecognized = || Error::UnrecognizedDirective {
+        directive: directive.trim().to_owned(),
+    };
+
+    let (key, value) = directive.split_once('=').ok_or_else(unrecognized)?;
+
+    let value = value
+        .trim()
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .ok_or_else(unrecognized)?;
+
+    match key.trim() {
+        "aux" => Ok(Directive::Aux(PathBuf::from(value))),
+        _ => Err(unrecognized()),
+    }
+}
@@ -13,10 +13,8 @@ pub(crate) enum Error {
         directory: PathBuf,
         source: cargo_metadata::Error,
     },
-    #[error("could not find package")]
-    CargoPackageNotFound,
-    #[error("unsupported rust edition: {edition:?}")]
-    UnsupportedRustEdition { edition: String },
+    #[error("could not find package: '{name}'")]
+    CargoPackageNotFound { name: String },
     #[error("could not remove directory: {path:?}")]
     RemovingDirectoryFailed {
         path: PathBuf,
@@ -27,6 +25,16 @@ pub(crate) enum Error {
         path: PathBuf,
         source: std::io::Error,
     },
+    #[error("could not read directory: {path:?}")]
+    ReadingDirectoryFailed {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("could not resolve path: {path:?}")]
+    ResolvingPathFailed {
+        path: PathBuf,
+        source: std::io::Error,
+    },
     #[error("could not read from file: {path:?}")]
     ReadingFileFailed {
         path: PathBuf,
@@ -37,10 +45,50 @@ pub(crate) enum Error {
         path: PathBuf,
         source: std::io::Error,
     },
+    #[error("could not copy file from {from:?} to {to:?}")]
+    CopyingFileFailed {
+        from: PathBuf,
+        to: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("could not find lockfile: {path:?}")]
+    CargoLockfileNotFound { path: PathBuf },
+    #[error("could not sync lockfile {path:?} with the generated test project: {stderr}")]
+    CargoLockfileSync { path: PathBuf, stderr: String },
+    #[error("could not find `main.rs` or `lib.rs` in test directory: {path:?}")]
+    TestEntryNotFound { path: PathBuf },
+    #[error("unrecognized directive: '{directive}'")]
+    UnrecognizedDirective { directive: String },
+    #[error("unrecognized annotation: '{annotation}'")]
+    UnrecognizedAnnotation { annotation: String },
+    #[error("span marker without any annotation on line {line}")]
+    UnannotatedSpanMarker { line: usize },
+    #[error("annotations only get verified by `cargo check`, which the test suite doesn't run: {path:?}")]
+    UnverifiedAnnotations { path: PathBuf },
+    #[error("annotations are only supported within the test case's crate root: {path:?}")]
+    MisplacedAnnotations { path: PathBuf },
+    #[error("dependency '{name}' conflicts with another dependency of the test project")]
+    DependencyConflict { name: String },
+    #[error("conflicting auxiliary crates named '{name}': {paths:?}")]
+    AuxiliaryCrateConflict { name: String, paths: Vec<PathBuf> },
+    #[error("fixture path must be relative and stay within its directory: {path:?}")]
+    InvalidFixturePath { path: PathBuf },
+    #[error("fixture path must not replace the test project or its generated files: {path:?}")]
+    ReservedFixturePath { path: PathBuf },
     #[error("could not spawn process: {0:?}")]
     SpawningProcessFailed(#[source] std::io::Error),
+    #[error("could not parse cargo manifest: {path:?}")]
+    CargoManifestParsingFailed {
+        path: PathBuf,
+        source: cargo_toml::Error,
+    },
     #[error("could not serialize cargo manifest to toml")]
     CargoManifestSerializationFailed(#[source] basic_toml::Error),
+    #[error("could not parse cargo config: {path:?}")]
+    CargoConfigParsingFailed {
+        path: PathBuf,
+        source: basic_toml::Error,
+    },
     #[error("could not serialize cargo config to toml")]
     CargoConfigSerializationFailed(#[source] basic_toml::Error),
     #[error("could not access glob path")]
@@ -5,7 +5,11 @@
 
 use std::{panic::Location, path::Path};
 
+mod annotation;
 mod cargo;
+mod config;
+mod diagnostic;
+mod directive;
 mod error;
 mod manifest;
 mod message;
@@ -27,6 +31,7 @@ pub(crate) const TRYEXPAND_DEBUG_LOG_ENV_KEY: &str = "TRYEXPAND_DEBUG_LOG";
 pub(crate) const OUT_RS_FILE_SUFFIX: &str = "out.rs";
 pub(crate) const OUT_TXT_FILE_SUFFIX: &str = "out.txt";
 pub(crate) const ERR_TXT_FILE_SUFFIX: &str = "err.txt";
+pub(crate) const ERR_JSON_FILE_SUFFIX: &str = "err.json";
 
 use crate::{
     test::Action,
@@ -136,6 +141,111 @@ where
     }
 }
 
+/// Run snapshot tests on files that match the provided paths/glob patterns,
+/// snapshotting the lints as they are produced by `cargo clippy [ARGS]`.
+///
+/// # Examples
+///
+/// Simple:
+///
+/// ```
+/// #[test]
+/// pub fn pass() {
+///     tryexpand::clippy(
+///         ["tests/expand/pass/*.rs"]
+///     ).expect_pass();
+/// }
+///
+/// #[test]
+/// pub fn fail() {
+///     tryexpand::clippy(
+///         ["tests/expand/fail/*.rs"]
+///     ).expect_fail();
+/// }
+/// ```
+///
+/// Advanced:
+///
+/// ```
+/// #[test]
+/// pub fn pass() {
+///     tryexpand::clippy(
+///         [
+///             "tests/expand/foo/pass/*.rs",
+///             "tests/expand/bar/pass/*.rs"
+///         ]
+///     )
+///     .clippy_warn("clippy::pedantic")
+///     .clippy_deny("clippy::unwrap_used")
+///     .expect_pass();
+/// }
+/// ```
+#[track_caller] // LOAD-BEARING, DO NOT REMOVE!
+pub fn clippy<I, P>(patterns: I) -> BuildTestSuite
+where
+    I: IntoIterator<Item = P>,
+    P: AsRef<Path>,
+{
+    match TestSuite::new(patterns, Action::Clippy, Location::caller()) {
+        Ok(test_suite) => BuildTestSuite(test_suite),
+        Err(err) => panic!("Error: {err:?}"),
+    }
+}
+
+/// Run snapshot tests on files that match the provided paths/glob patterns,
+/// snapshotting the warnings as they are produced by `cargo doc [ARGS]`.
+///
+/// Like with [`run_doc_tests()`], the test files get documented as library targets,
+/// which are named after their file's stem (e.g. `tests/foo.rs` becomes `foo`).
+///
+/// # Examples
+///
+/// Simple:
+///
+/// ```
+/// #[test]
+/// pub fn pass() {
+///     tryexpand::doc(
+///         ["tests/expand/pass/*.rs"]
+///     ).expect_pass();
+/// }
+///
+/// #[test]
+/// pub fn fail() {
+///     tryexpand::doc(
+///         ["tests/expand/fail/*.rs"]
+///     ).expect_fail();
+/// }
+/// ```
+///
+/// Advanced:
+///
+/// ```
+/// #[test]
+/// pub fn pass() {
+///     tryexpand::doc(
+///         [
+///             "tests/expand/foo/pass/*.rs",
+///             "tests/expand/bar/pass/*.rs"
+///         ]
+///     )
+///     .args(["--features", "test-feature"])
+///     .envs([("RUSTDOCFLAGS", "-Drustdoc::broken_intra_doc_links")])
+///     .expect_pass();
+/// }
+/// ```
+#[track_caller] // LOAD-BEARING, DO NOT REMOVE!
+pub fn doc<I, P>(patterns: I) -> BuildTestSuite
+where
+    I: IntoIterator<Item = P>,
+    P: AsRef<Path>,
+{
+    match TestSuite::new(patterns, Action::Doc, Location::caller()) {
+        Ok(test_suite) => BuildTestSuite(test_suite),
+        Err(err) => panic!("Error: {err:?}"),
+    }
+}
+
 /// Run snapshot tests on files that match the provided paths/glob patterns,
 /// snapshotting the stdout/stderr output as it is produced by `cargo run [ARGS]`.
 ///
@@ -237,3 +347,57 @@ where
         Err(err) => panic!("Error: {err:?}"),
     }
 }
+
+/// Run snapshot tests on files that match the provided paths/glob patterns,
+/// snapshotting the stdout/stderr output as it is produced by `cargo test --doc [ARGS]`.
+///
+/// Since binaries have no doctests, the test files get compiled as library targets,
+/// which are named after their file's stem (e.g. `tests/foo.rs` becomes `foo`).
+///
+/// # Examples
+///
+/// Simple:
+///
+/// ```
+/// #[test]
+/// pub fn pass() {
+///     tryexpand::run_doc_tests(
+///         ["tests/expand/pass/*.rs"]
+///     ).expect_pass();
+/// }
+///
+/// #[test]
+/// pub fn fail() {
+///     tryexpand::run_doc_tests(
+///         ["tests/expand/fail/*.rs"]
+///     ).expect_fail();
+/// }
+/// ```
+///
+/// Advanced:
+///
+/// ```
+/// #[test]
+/// pub fn pass() {
+///     tryexpand::run_doc_tests(
+///         [
+///             "tests/expand/foo/pass/*.rs",
+///             "tests/expand/bar/pass/*.rs"
+///         ]
+///     )
+///     .args(["--features", "test-feature"])
+///     .envs([("MY_ENV", "my env var value")])
+///     .expect_pass();
+/// }
+/// ```
+#[track_caller] // LOAD-BEARING, DO NOT REMOVE!
+pub fn run_doc_tests<I, P>(patterns: I) -> BuildTestSuite
+where
+    I: IntoIterator<Item = P>,
+    P: AsRef<Path>,
+{
+    match TestSuite::new(patterns, Action::DocTest, Location::caller()) {
+        Ok(test_suite) => BuildTestSuite(test_suite),
+        Err(err) => panic!("Error: {err:?}"),
+    }
+}
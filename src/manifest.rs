@@ -1,50 +1,77 @@
-use std::collections::BTreeMap;
+use std::{
+    collections::{btree_map::Entry, BTreeMap},
+    path::{Path, PathBuf},
+};
 
 use cargo_metadata::{
-    Dependency as SourceDependency, DependencyKind as SourceDependencyKind,
-    Edition as SourceEdition, Metadata as SourceMetadata, Package as SourcePackage,
+    semver::VersionReq, Dependency as SourceDependency, DependencyKind as SourceDependencyKind,
+    Metadata as SourceMetadata, Package as SourcePackage, Source as SourceDependencySource,
 };
 use cargo_toml::{
-    Badges, Dependency, DependencyDetail, DepsSet, Edition, Inheritable, Manifest, Package,
-    PatchSet, Product, Profiles, TargetDepsSet, Workspace,
+    Badges, Dependency, DependencyDetail, DepsSet, Inheritable, Manifest, OptionalFile, Package,
+    Product, Profiles, TargetDepsSet, Workspace,
 };
 
 use crate::{
     error::{Error, Result},
-    project::Project,
+    options::{Options, TargetKind},
+    project::{self, Project},
     test::Test,
+    utils,
 };
 
 pub(crate) fn cargo_manifest<'a, I>(
-    _source_metadata: &SourceMetadata,
+    source_metadata: &SourceMetadata,
     source_package: &SourcePackage,
     test_crate_name: &str,
     project: &Project,
     tests: I,
+    options: &Options,
 ) -> Result<Manifest>
 where
     I: IntoIterator<Item = &'a Test>,
 {
     #![allow(clippy::field_reassign_with_default)]
 
-    let mut package: Package = Package::new(test_crate_name.to_owned(), "0.0.0".to_owned());
-
-    package.edition = match source_package.edition {
-        SourceEdition::E2015 => Inheritable::Set(Edition::E2015),
-        SourceEdition::E2018 => Inheritable::Set(Edition::E2018),
-        SourceEdition::E2021 => Inheritable::Set(Edition::E2021),
-        SourceEdition::E2024 => Inheritable::Set(Edition::E2024),
-        edition => {
-            return Err(Error::UnsupportedRustEdition {
-                edition: edition.to_string(),
-            })
-        }
-    };
+    let workspace_root = source_metadata.workspace_root.as_std_path();
+    let workspace_manifest = workspace_root_manifest(workspace_root)?;
+
+    // Cargo resolves `workspace = true` fields for us when generating metadata,
+    // but doesn't include everything (like lints) in it and flattens dependencies
+    // into their resolved form, so we resolve the package's manifest against the
+    // workspace root's manifest ourselves and prefer its declarations:
+    let source_manifest =
+        source_package_manifest(source_package, &workspace_manifest, workspace_root)?;
+    let source_manifest_package = source_manifest.package();
+    let source_package_dir = source_package.manifest_path.parent().unwrap().as_std_path();
+
+    let mut package: Package = Package::new(
+        test_crate_name.to_owned(),
+        source_manifest_package.version(),
+    );
 
-    package.rust_version = source_package
+    package.edition = Inheritable::Set(source_manifest_package.edition());
+    package
         .rust_version
+        .clone_from(&source_manifest_package.rust_version);
+    package.authors.clone_from(&source_manifest_package.authors);
+    package
+        .description
+        .clone_from(&source_manifest_package.description);
+    package
+        .homepage
+        .clone_from(&source_manifest_package.homepage);
+    package
+        .documentation
+        .clone_from(&source_manifest_package.documentation);
+    package
+        .repository
+        .clone_from(&source_manifest_package.repository);
+    package.license.clone_from(&source_manifest_package.license);
+    package.build = options
+        .build_script
         .as_ref()
-        .map(|version| Inheritable::Set(version.to_string()));
+        .map(|path| OptionalFile::Path(project.manifest_dir.join(path)));
 
     let mut dependencies = BTreeMap::default();
     let mut dev_dependencies = BTreeMap::default();
@@ -53,66 +80,115 @@ where
     let dependency_path = project.manifest_dir.display().to_string();
     let mut dependency = DependencyDetail::default();
     dependency.path = Some(dependency_path);
-    dependency.default_features = true;
+    // The package's default features get enabled through the forwarded `default` feature,
+    // which allows for `--no-default-features` to behave as expected:
+    dependency.default_features = false;
 
-    let dependency_name = source_package.name.clone();
+    // If the crate is supposed to be made available under an alias,
+    // we need to declare it as a renamed dependency:
+    let dependency_name = match &options.crate_alias {
+        Some(alias) => {
+            dependency.package = Some(source_package.name.to_string());
+            alias.clone()
+        }
+        None => source_package.name.to_string(),
+    };
 
     dependencies.insert(
-        dependency_name.to_string(),
+        dependency_name.clone(),
         Dependency::Detailed(Box::new(dependency)),
     );
 
-    for source_dependency in &source_package.dependencies {
+    let mut target = TargetDepsSet::default();
+
+    // Dev-dependencies come last, so that they can get merged into normal dependencies:
+    let (source_dev_dependencies, source_dependencies): (Vec<_>, Vec<_>) = source_package
+        .dependencies
+        .iter()
+        .partition(|dependency| dependency.kind == SourceDependencyKind::Development);
+
+    for source_dependency in source_dependencies
+        .into_iter()
+        .chain(source_dev_dependencies)
+    {
         let SourceDependency {
-            name,
-            source: _,
-            req,
             kind,
-            optional,
-            uses_default_features,
-            features,
-            target: _,
-            rename,
-            registry,
-            path,
+            target: platform,
             ..
         } = source_dependency;
 
-        let mut dependency = DependencyDetail::default();
-        dependency.package.clone_from(rename);
-        dependency.version = Some(req.to_string());
-        dependency.optional = *optional;
-        dependency.default_features = *uses_default_features;
-        dependency.features.clone_from(features);
-        dependency.registry.clone_from(registry);
-        dependency.path = path.as_ref().map(|path| path.to_string());
-        dependency.default_features = true;
+        let (name, dependency) = match declared_dependency(&source_manifest, source_dependency) {
+            Some(mut dependency) => {
+                absolutize_dependency_path(&mut dependency, source_package_dir);
+                (dependency_key(source_dependency), dependency)
+            }
+            None => dependency_from_source(source_dependency),
+        };
+
+        // Platform-specific dependencies go into their corresponding
+        // `[target.<platform>.*dependencies]` tables:
+        let (dependencies, dev_dependencies, build_dependencies) = match platform {
+            Some(platform) => {
+                let target = target.entry(platform.to_string()).or_default();
+                (
+                    &mut target.dependencies,
+                    &mut target.dev_dependencies,
+                    &mut target.build_dependencies,
+                )
+            }
+            None => (
+                &mut dependencies,
+                &mut dev_dependencies,
+                &mut build_dependencies,
+            ),
+        };
 
         match kind {
             SourceDependencyKind::Normal => {
-                dependencies.insert(name.to_owned(), Dependency::Detailed(Box::new(dependency)));
+                dependencies.insert(name, dependency);
+            }
+            SourceDependencyKind::Development if options.dev_dependencies => {
+                // Binaries have no access to dev-dependencies,
+                // so we need to declare them as normal dependencies:
+                merge_dependency(dependencies, name, dependency);
             }
             SourceDependencyKind::Development => {
-                dev_dependencies
-                    .insert(name.to_owned(), Dependency::Detailed(Box::new(dependency)));
+                dev_dependencies.insert(name, dependency);
             }
             SourceDependencyKind::Build => {
-                build_dependencies
-                    .insert(name.to_owned(), Dependency::Detailed(Box::new(dependency)));
+                build_dependencies.insert(name, dependency);
             }
             _ => todo!(),
         }
     }
 
+    for name in &options.workspace_dependencies {
+        let (key, dependency) = workspace_dependency(source_metadata, name)?;
+        let tables = [&dependencies, &dev_dependencies, &build_dependencies];
+        if declares_dependency(tables, &target, &key) {
+            return Err(Error::DependencyConflict { name: key });
+        }
+        dependencies.insert(key, dependency);
+    }
+
+    for (name, dependency) in &options.dependencies {
+        let tables = [&dependencies, &dev_dependencies, &build_dependencies];
+        if declares_dependency(tables, &target, name) {
+            return Err(Error::DependencyConflict { name: name.clone() });
+        }
+        let mut dependency = dependency.clone();
+        absolutize_dependency_path(&mut dependency, &project.manifest_dir);
+        dependencies.insert(name.clone(), dependency);
+    }
+
     let features = source_package
         .features
         .iter()
         .map(|(key, values)| {
-            let mut values: Vec<String> = values
-                .iter()
-                .filter(|value| value.starts_with("dep:"))
-                .cloned()
-                .collect();
+            // Since the test project mirrors the package's dependencies, we can keep
+            // the feature's values as they are (`dep:`, `dep/feat`, `dep?/feat`, etc.)
+            // and additionally forward the feature to the package itself:
+            let mut values = values.clone();
             values.push(format!("{dependency_name}/{key}"));
             (key.clone(), values)
         })
@@ -122,19 +198,47 @@ where
     lib_product.name = Some(test_crate_name.replace('-', "_").to_owned());
     lib_product.path = Some("lib.rs".to_owned());
 
-    let bin: Vec<_> = tests
-        .into_iter()
-        .map(|test| {
-            let mut bin_product = Product::default();
-            let test_path = std::env::current_dir().unwrap().join(&test.path);
-            bin_product.name = Some(test.bin.to_owned());
-            bin_product.path = Some(test_path.display().to_string());
-            bin_product
-        })
-        .collect();
+    // Library targets need packages of their own (as each package can only have
+    // a single library), which we add as members of the test project's workspace:
+    let (bin, members): (Vec<_>, Vec<_>) = match options.target_kind {
+        TargetKind::Bin => {
+            let bin = tests
+                .into_iter()
+                .map(|test| {
+                    let mut bin_product = Product::default();
+                    bin_product.name = Some(test.bin.to_owned());
+                    bin_product.path = Some(test_source_path(test));
+                    bin_product
+                })
+                .collect();
+            (bin, vec![])
+        }
+        TargetKind::Lib => {
+            let members = tests
+                .into_iter()
+                .map(|test| project::library_package_dir(test).display().to_string())
+                .collect();
+            (vec![], members)
+        }
+    };
+
+    // Patches and replacements only take effect from within the workspace root's manifest,
+    // so we need to carry them over from there, rather than the package's manifest:
+    #[allow(deprecated)]
+    let (mut replace, mut patch) = (workspace_manifest.replace, workspace_manifest.patch);
+
+    for dependency in replace.values_mut() {
+        absolutize_dependency_path(dependency, workspace_root);
+    }
+
+    for dependencies in patch.values_mut() {
+        for dependency in dependencies.values_mut() {
+            absolutize_dependency_path(dependency, workspace_root);
+        }
+    }
 
     let workspace = Workspace {
-        members: vec![],
+        members,
         default_members: vec![],
         package: None,
         exclude: vec![],
@@ -151,19 +255,436 @@ where
         dependencies,
         dev_dependencies,
         build_dependencies,
-        target: TargetDepsSet::default(),
+        target,
         features,
-        replace: DepsSet::default(),
-        patch: PatchSet::default(),
+        replace,
+        patch,
         lib: Some(lib_product),
-        profile: Profiles::default(),
+        profile: profiles(options),
         badges: Badges::default(),
         bin,
         bench: vec![],
         test: vec![],
         example: vec![],
-        lints: Inheritable::Set(BTreeMap::default()),
+        lints: source_manifest.lints,
+    };
+
+    Ok(manifest)
+}
+
+/// An auxiliary crate, which gets compiled as a library package of its own
+/// and made available to test files as a dependency of the test project
+/// (or, for library targets, of the packages of the test files declaring it).
+#[derive(Clone, Debug)]
+pub(crate) struct Auxiliary {
+    // The crate's name (derived from its file's stem).
+    pub name: String,
+    // The name of the crate's package.
+    pub package: String,
+    // The crate's source file.
+    pub path: PathBuf,
+}
+
+/// Returns the auxiliary crates declared by the given tests (deduplicated by name).
+pub(crate) fn auxiliaries<'a, I>(test_crate_name: &str, tests: I) -> Result<Vec<Auxiliary>>
+where
+    I: IntoIterator<Item = &'a Test>,
+{
+    let mut auxiliaries: BTreeMap<String, Auxiliary> = BTreeMap::default();
+
+    for path in tests.into_iter().flat_map(|test| &test.auxiliaries) {
+        let name = crate_name_from_path(path);
+        let path = path.clone();
+
+        match auxiliaries.entry(name.clone()) {
+            Entry::Vacant(entry) => {
+                entry.insert(Auxiliary {
+                    package: format!("{test_crate_name}_aux_{name}"),
+                    name,
+                    path,
+                });
+            }
+            Entry::Occupied(entry) if entry.get().path == path => {}
+            Entry::Occupied(entry) => {
+                return Err(Error::AuxiliaryCrateConflict {
+                    name,
+                    paths: vec![entry.get().path.clone(), path],
+                });
+            }
+        }
+    }
+
+    Ok(auxiliaries.into_values().collect())
+}
+
+/// Returns the auxiliary crates declared by the given test.
+pub(crate) fn test_auxiliaries<'a>(
+    test: &Test,
+    auxiliaries: &'a [Auxiliary],
+) -> Vec<&'a Auxiliary> {
+    auxiliaries
+        .iter()
+        .filter(|auxiliary| test.auxiliaries.contains(&auxiliary.path))
+        .collect()
+}
+
+/// Adds the auxiliary crates as dependencies to the given manifest.
+pub(crate) fn add_auxiliary_dependencies<'a, I>(
+    manifest: &mut Manifest,
+    project: &Project,
+    auxiliaries: I,
+) -> Result<()>
+where
+    I: IntoIterator<Item = &'a Auxiliary>,
+{
+    for auxiliary in auxiliaries {
+        let tables = [
+            &manifest.dependencies,
+            &manifest.dev_dependencies,
+            &manifest.build_dependencies,
+        ];
+        if declares_dependency(tables, &manifest.target, &auxiliary.name) {
+            return Err(Error::DependencyConflict {
+                name: auxiliary.name.clone(),
+            });
+        }
+
+        let package_dir = project.dir.join(project::auxiliary_package_dir(auxiliary));
+        let dependency = DependencyDetail {
+            package: Some(auxiliary.package.clone()),
+            path: Some(package_dir.display().to_string()),
+            ..Default::default()
+        };
+        manifest.dependencies.insert(
+            auxiliary.name.clone(),
+            Dependency::Detailed(Box::new(dependency)),
+        );
+    }
+
+    Ok(())
+}
+
+// Returns `true` if any of the (platform-specific) dependency tables declares the name:
+fn declares_dependency(tables: [&DepsSet; 3], target: &TargetDepsSet, name: &str) -> bool {
+    tables.iter().any(|table| table.contains_key(name))
+        || target.values().any(|target| {
+            [
+                &target.dependencies,
+                &target.dev_dependencies,
+                &target.build_dependencies,
+            ]
+            .iter()
+            .any(|table| table.contains_key(name))
+        })
+}
+
+/// Returns the manifest of the package that compiles the given auxiliary crate as its library,
+/// which is derived from the test project's own manifest (i.e. the workspace root).
+pub(crate) fn auxiliary_manifest(root_manifest: &Manifest, auxiliary: &Auxiliary) -> Manifest {
+    #![allow(clippy::field_reassign_with_default)]
+
+    let mut lib_product = Product::default();
+    lib_product.name = Some(auxiliary.name.clone());
+    lib_product.path = Some(auxiliary.path.display().to_string());
+
+    let mut manifest = member_manifest(root_manifest, &auxiliary.package, lib_product);
+
+    // Build scripts are meant for the test files, not the crates they depend on:
+    if let Some(package) = manifest.package.as_mut() {
+        package.build = None;
+    }
+
+    manifest
+}
+
+/// Returns the manifest of the package that compiles the given test file as its library,
+/// which is derived from the test project's own manifest (i.e. the workspace root).
+pub(crate) fn library_manifest(root_manifest: &Manifest, test: &Test) -> Manifest {
+    #![allow(clippy::field_reassign_with_default)]
+
+    let mut lib_product = Product::default();
+    lib_product.name = Some(library_name(test));
+    lib_product.path = Some(test_source_path(test));
+
+    member_manifest(root_manifest, &test.bin, lib_product)
+}
+
+fn member_manifest(root_manifest: &Manifest, package_name: &str, lib_product: Product) -> Manifest {
+    let mut manifest = root_manifest.clone();
+
+    if let Some(package) = manifest.package.as_mut() {
+        package.name = package_name.to_owned();
+    }
+
+    // The member's library is its only target, whatever the root declares:
+    manifest.lib = Some(lib_product);
+    manifest.bin = vec![];
+    manifest.example = vec![];
+    manifest.test = vec![];
+    manifest.bench = vec![];
+
+    // Workspace-level settings only take effect from within the workspace root's manifest:
+    manifest.workspace = None;
+    manifest.patch = Default::default();
+    #[allow(deprecated)]
+    {
+        manifest.replace = Default::default();
+    }
+    manifest.profile = Profiles::default();
+
+    manifest
+}
+
+/// Returns the profiles for the generated project, with the settings of `options`
+/// applied to the profile in use (from which `test`/`bench` inherit theirs).
+fn profiles(options: &Options) -> Profiles {
+    let mut profiles = Profiles::default();
+
+    let profile = Some(options.profile.clone());
+    if options.release {
+        profiles.release = profile;
+    } else {
+        profiles.dev = profile;
+    }
+
+    profiles
+}
+
+// Libraries are named after their test file's stem, so that
+// doctests can refer to their items in a predictable manner:
+fn library_name(test: &Test) -> String {
+    crate_name_from_path(&test.path)
+}
+
+fn crate_name_from_path(path: &Path) -> String {
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    let name: String = stem
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect();
+
+    match name.chars().next() {
+        Some(c) if !c.is_ascii_digit() => name,
+        _ => format!("_{name}"),
+    }
+}
+
+fn test_source_path(test: &Test) -> String {
+    test.absolute_entry_path().display().to_string()
+}
+
+// Returns a path dependency on the given workspace member, together with its key.
+fn workspace_dependency(
+    source_metadata: &SourceMetadata,
+    name: &str,
+) -> Result<(String, Dependency)> {
+    let package = source_metadata
+        .workspace_packages()
+        .into_iter()
+        .find(|package| package.name.as_str() == name)
+        .ok_or_else(|| Error::CargoPackageNotFound {
+            name: name.to_owned(),
+        })?;
+
+    let manifest_dir = package.manifest_path.parent().unwrap();
+
+    let dependency = DependencyDetail {
+        path: Some(manifest_dir.to_string()),
+        ..Default::default()
+    };
+
+    Ok((name.to_owned(), Dependency::Detailed(Box::new(dependency))))
+}
+
+// Returns the dependency, together with the key it should get declared under.
+fn dependency_from_source(source_dependency: &SourceDependency) -> (String, Dependency) {
+    #![allow(clippy::field_reassign_with_default)]
+
+    let SourceDependency {
+        name,
+        source,
+        req,
+        optional,
+        uses_default_features,
+        features,
+        rename,
+        registry,
+        path,
+        ..
+    } = source_dependency;
+
+    let mut dependency = DependencyDetail::default();
+
+    // For renamed dependencies `name` refers to the package's actual name,
+    // while `rename` refers to the key it was declared under:
+    let key = match rename {
+        Some(rename) => {
+            dependency.package = Some(name.clone());
+            rename.clone()
+        }
+        None => name.clone(),
     };
 
+    // Path and git dependencies don't need a version, but registry ones always do
+    // (as cargo rejects a dependency that specifies neither):
+    let is_git = source
+        .as_ref()
+        .is_some_and(|source| source.repr.starts_with("git+"));
+    if *req != VersionReq::STAR || (path.is_none() && !is_git) {
+        dependency.version = Some(req.to_string());
+    }
+    dependency.optional = *optional;
+    dependency.default_features = *uses_default_features;
+    dependency.features.clone_from(features);
+    dependency.registry_index.clone_from(registry);
+    dependency.path = path.as_ref().map(|path| path.to_string());
+
+    if let Some(source) = source {
+        apply_git_source(&mut dependency, source);
+    }
+
+    (key, Dependency::Detailed(Box::new(dependency)))
+}
+
+// Returns the key the dependency was declared under.
+fn dependency_key(source_dependency: &SourceDependency) -> String {
+    match &source_dependency.rename {
+        Some(rename) => rename.clone(),
+        None => source_dependency.name.clone(),
+    }
+}
+
+// Returns the dependency's declaration from the package's (completed) manifest, if any.
+//
+// Unlike the metadata's view of the dependency, the declaration keeps the dependency's
+// original specification (e.g. git references, which cargo doesn't escape in the source),
+// with `workspace = true` having been resolved against the workspace root's manifest.
+fn declared_dependency(
+    manifest: &Manifest,
+    source_dependency: &SourceDependency,
+) -> Option<Dependency> {
+    let key = dependency_key(source_dependency);
+
+    let (dependencies, dev_dependencies, build_dependencies) = match &source_dependency.target {
+        Some(platform) => {
+            let target = manifest.target.get(&platform.to_string())?;
+            (
+                &target.dependencies,
+                &target.dev_dependencies,
+                &target.build_dependencies,
+            )
+        }
+        None => (
+            &manifest.dependencies,
+            &manifest.dev_dependencies,
+            &manifest.build_dependencies,
+        ),
+    };
+
+    let dependencies = match source_dependency.kind {
+        SourceDependencyKind::Normal => dependencies,
+        SourceDependencyKind::Development => dev_dependencies,
+        SourceDependencyKind::Build => build_dependencies,
+        _ => return None,
+    };
+
+    let mut dependency = dependencies.get(&key)?.clone();
+    let detail = dependency.try_detail_mut().ok()?.clone();
+
+    Some(Dependency::Detailed(Box::new(detail)))
+}
+
+// Git sources have the following format:
+//
+//     git+<URL>[?branch=<BRANCH>|?tag=<TAG>|?rev=<REV>][#<COMMIT>]
+//
+fn apply_git_source(dependency: &mut DependencyDetail, source: &SourceDependencySource) {
+    let Some(url) = source.repr.strip_prefix("git+") else {
+        return;
+    };
+
+    let url = url.split_once('#').map_or(url, |(url, _commit)| url);
+
+    let (url, query) = url.split_once('?').unwrap_or((url, ""));
+
+    dependency.git = Some(url.to_owned());
+
+    for (key, value) in query.split('&').filter_map(|pair| pair.split_once('=')) {
+        match key {
+            "branch" => dependency.branch = Some(value.to_owned()),
+            "tag" => dependency.tag = Some(value.to_owned()),
+            "rev" => dependency.rev = Some(value.to_owned()),
+            _ => {}
+        }
+    }
+}
+
+// Merges the dependency's features into an existing dependency of the same name,
+// or inserts it as is, if there isn't one yet.
+fn merge_dependency(dependencies: &mut DepsSet, name: String, dependency: Dependency) {
+    match dependencies.entry(name) {
+        Entry::Vacant(entry) => {
+            entry.insert(dependency);
+        }
+        Entry::Occupied(mut entry) => {
+            let (Dependency::Detailed(existing), Dependency::Detailed(dependency)) =
+                (entry.get_mut(), dependency)
+            else {
+                return;
+            };
+
+            existing.default_features |= dependency.default_features;
+
+            for feature in dependency.features {
+                if !existing.features.contains(&feature) {
+                    existing.features.push(feature);
+                }
+            }
+        }
+    }
+}
+
+fn workspace_root_manifest(workspace_root: &Path) -> Result<Manifest> {
+    let path = workspace_root.join("Cargo.toml");
+    let bytes = utils::read(&path)?;
+    Manifest::from_slice(&bytes)
+        .map_err(|source| Error::CargoManifestParsingFailed { path, source })
+}
+
+fn source_package_manifest(
+    source_package: &SourcePackage,
+    workspace_manifest: &Manifest,
+    workspace_root: &Path,
+) -> Result<Manifest> {
+    let path = source_package.manifest_path.as_std_path().to_owned();
+    let bytes = utils::read(&path)?;
+
+    let mut manifest =
+        Manifest::from_slice(&bytes).map_err(|source| Error::CargoManifestParsingFailed {
+            path: path.clone(),
+            source,
+        })?;
+
+    manifest
+        .complete_from_path_and_workspace(&path, Some((workspace_manifest, workspace_root)))
+        .map_err(|source| Error::CargoManifestParsingFailed { path, source })?;
+
     Ok(manifest)
 }
+
+// Relative paths are relative to the manifest they were declared in,
+// which isn't where the test project's manifest is located, so we
+// need to make them absolute:
+fn absolutize_dependency_path(dependency: &mut Dependency, base_dir: &Path) {
+    let Dependency::Detailed(detail) = dependency else {
+        return;
+    };
+
+    if let Some(path) = &mut detail.path {
+        *path = base_dir.join(&*path).display().to_string();
+    }
+}
@@ -3,6 +3,7 @@ use std::path::Path;
 use yansi::{Paint, Painted};
 
 use crate::{
+    annotation::AnnotationMismatch,
     error::{Error, Result},
     test::TestOutcome,
     TRYEXPAND_ENV_KEY, TRYEXPAND_ENV_VAL_OVERWRITE,
@@ -38,6 +39,9 @@ pub(crate) fn report_outcome(source_path: &Path, outcome: &TestOutcome) {
         TestOutcome::SnapshotUnexpected { path, content } => {
             snapshot_unexpected(source_path, path, content);
         }
+        TestOutcome::AnnotationMismatch(mismatch) => {
+            annotation_mismatch(source_path, mismatch);
+        }
         TestOutcome::UnexpectedSuccess {
             source,
             expanded,
@@ -165,6 +169,28 @@ pub(crate) fn snapshot_unexpected(path: &Path, snapshot_path: &Path, snapshot: &
     eprintln!("--------------------------");
 }
 
+pub(crate) fn annotation_mismatch(path: &Path, mismatch: &AnnotationMismatch) {
+    eprintln!("{path} - {}", "MISMATCH".red(), path = path.display());
+    eprintln!("--------------------------");
+
+    eprintln!("{}", "Unexpected mismatch of annotations:".red());
+
+    if !mismatch.unmatched.is_empty() {
+        eprintln!();
+        eprintln!("NOT FOUND:");
+        eprintln!();
+        print_block(&mismatch.unmatched.join("\n"), Paint::red);
+    }
+    if !mismatch.unexpected.is_empty() {
+        eprintln!();
+        eprintln!("UNEXPECTED:");
+        eprintln!();
+        print_block(&mismatch.unexpected.join("\n"), Paint::red);
+    }
+
+    eprintln!("--------------------------");
+}
+
 pub(crate) fn unexpected_success(
     path: &Path,
     source: &str,
@@ -1,4 +1,7 @@
 mod check;
+mod clippy;
+mod diagnostics;
+mod doc;
 mod expand;
 mod run;
 mod test;
@@ -6,6 +9,12 @@ mod utils;
 
 pub(crate) use self::{
     check::{stderr as check_stderr, stdout as check_stdout},
+    clippy::{stderr as clippy_stderr, stdout as clippy_stdout},
+    diagnostics::{
+        file as diagnostics_file, is_test_file, json as diagnostics_json,
+        normalize as normalize_diagnostics, test_path as diagnostics_test_path,
+    },
+    doc::{stderr as doc_stderr, stdout as doc_stdout},
     expand::{stderr as expand_stderr, stdout as expand_stdout},
     run::{stderr as run_stderr, stdout as run_stdout},
     test::{stderr as test_stderr, stdout as test_stdout},
@@ -1,7 +1,7 @@
 use std::borrow::Cow;
 
 use crate::{
-    cargo::{line_is_error, line_is_warning, line_should_be_omitted},
+    cargo::{line_is_error, line_is_status, line_is_warning, line_should_be_omitted},
     normalization::utils::{
         apply_regex_replacements, apply_replacements, post_process, project_info_replacements,
     },
@@ -10,14 +10,35 @@ use crate::{
     test::Test,
 };
 
+/// Extracts the `cargo:warning=…` output of the test project's build script
+/// from the (stderr) output of `cargo check`, where cargo reports it.
 pub(crate) fn stdout<'a>(
-    _input: Cow<'a, str>,
-    _project: &Project,
-    _test: &Test,
-    _filters: &[RegexFilter],
+    input: Cow<'a, str>,
+    project: &Project,
+    test: &Test,
+    filters: &[RegexFilter],
 ) -> Option<Cow<'a, str>> {
-    // The stdout of `cargo check` isn't really that interesting to us.
-    None
+    let mut messages: Vec<&str> = vec![];
+
+    for message in input
+        .lines()
+        .filter_map(|line| build_script_warning(line, project, test))
+    {
+        // Cargo repeats the build script's warnings if compilation fails:
+        if !messages.contains(&message) {
+            messages.push(message);
+        }
+    }
+
+    let output = messages
+        .into_iter()
+        .map(|message| format!("warning: {message}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let output = apply_regex_replacements(Cow::from(output), filters, FilterTarget::Stdout);
+
+    post_process(output)
 }
 
 pub(crate) fn stderr<'a>(
@@ -25,6 +46,7 @@ pub(crate) fn stderr<'a>(
     project: &Project,
     test: &Test,
     filters: &[RegexFilter],
+    keep_warnings: bool,
 ) -> Option<Cow<'a, str>> {
     let replacements = project_info_replacements(project, test);
 
@@ -32,9 +54,11 @@ pub(crate) fn stderr<'a>(
 
     let output = trimmed_input
         .lines()
-        .skip_while(|line| !line_is_error(line))
+        .skip_while(|line| !(line_is_error(line) || (keep_warnings && line_is_warning(line))))
         .filter(|line| !line_should_be_omitted(line))
-        .filter(|line| !line_is_warning(line))
+        .filter(|line| !line_is_status(line))
+        .filter(|line| keep_warnings || !line_is_warning(line))
+        .filter(|line| build_script_warning(line, project, test).is_none())
         .map(|line| {
             let replacements = replacements.iter().map(|(p, r)| (p.as_str(), r.as_str()));
             apply_replacements(Cow::from(line), replacements)
@@ -46,3 +70,14 @@ pub(crate) fn stderr<'a>(
 
     post_process(output)
 }
+
+/// Returns the message of a warning emitted by the build script of
+/// one of the test project's packages (e.g. `warning: <package>@<version>: <message>`).
+fn build_script_warning<'a>(line: &'a str, project: &Project, test: &Test) -> Option<&'a str> {
+    let (package, rest) = line.strip_prefix("warning: ")?.split_once('@')?;
+    if package != project.name && package != test.bin {
+        return None;
+    }
+    let (_version, message) = rest.split_once(": ")?;
+    Some(message)
+}
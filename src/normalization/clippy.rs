@@ -0,0 +1,36 @@
+use std::{borrow::Cow, sync::LazyLock};
+
+use regex::Regex;
+
+use crate::{options::RegexFilter, project::Project, test::Test};
+
+// Clippy's lint links contain the toolchain's version, e.g.
+// `https://rust-lang.github.io/rust-clippy/rust-1.95.0/index.html#len_zero`,
+// which we normalize as `<VERSION>` to keep the snapshots stable:
+static VERSION_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"/rust-clippy/[^/]+/index\.html").unwrap());
+
+pub(crate) fn stdout<'a>(
+    _input: Cow<'a, str>,
+    _project: &Project,
+    _test: &Test,
+    _filters: &[RegexFilter],
+) -> Option<Cow<'a, str>> {
+    // The stdout of `cargo clippy` isn't really that interesting to us.
+    None
+}
+
+pub(crate) fn stderr<'a>(
+    input: Cow<'a, str>,
+    project: &Project,
+    test: &Test,
+    filters: &[RegexFilter],
+) -> Option<Cow<'a, str>> {
+    let input = match VERSION_REGEX.replace_all(&input, "/rust-clippy/<VERSION>/index.html") {
+        Cow::Owned(output) => Cow::Owned(output),
+        Cow::Borrowed(_) => input,
+    };
+
+    // Clippy's lints are what we're after, so we always keep its warnings:
+    super::check::stderr(input, project, test, filters, true)
+}
@@ -0,0 +1,97 @@
+use std::{borrow::Cow, path::Path};
+
+use cargo_metadata::diagnostic::DiagnosticLevel;
+
+use crate::{
+    diagnostic::Diagnostic,
+    normalization::utils::{
+        apply_regex_replacements, apply_replacements, post_process, project_info_replacements,
+    },
+    options::{FilterTarget, RegexFilter},
+    project::Project,
+    test::Test,
+};
+
+/// Normalizes the diagnostics' file names and messages the same way as
+/// the (human-readable) stderr output, so that they are stable across environments.
+pub(crate) fn normalize(
+    diagnostics: Vec<Diagnostic>,
+    project: &Project,
+    test: &Test,
+) -> Vec<Diagnostic> {
+    let replacements = project_info_replacements(project, test);
+    let replace = |string: String| {
+        let replacements = replacements.iter().map(|(p, r)| (p.as_str(), r.as_str()));
+        apply_replacements(Cow::from(string), replacements).into_owned()
+    };
+
+    fn normalize_diagnostic(
+        mut diagnostic: Diagnostic,
+        replace: &dyn Fn(String) -> String,
+    ) -> Diagnostic {
+        diagnostic.message = replace(diagnostic.message);
+        for span in &mut diagnostic.spans {
+            span.file = replace(std::mem::take(&mut span.file));
+            span.label = span.label.take().map(replace);
+        }
+        diagnostic.children = diagnostic
+            .children
+            .into_iter()
+            .map(|child| normalize_diagnostic(child, replace))
+            .collect();
+        diagnostic
+    }
+
+    diagnostics
+        .into_iter()
+        .map(|diagnostic| normalize_diagnostic(diagnostic, &replace))
+        .collect()
+}
+
+/// Returns the file name that the normalized diagnostics refer to the test's entry file by.
+pub(crate) fn file(project: &Project, test: &Test) -> String {
+    normalize_path(&test.absolute_entry_path(), project, test)
+}
+
+/// Returns the path that the normalized diagnostics refer to the test case by
+/// (i.e. either its file, or its directory).
+pub(crate) fn test_path(project: &Project, test: &Test) -> String {
+    normalize_path(&test.absolute_path(), project, test)
+}
+
+/// Returns whether the (normalized) file belongs to the test case with the given (normalized) path.
+pub(crate) fn is_test_file(file: &str, test_path: &str) -> bool {
+    match file.strip_prefix(test_path) {
+        Some(rest) => rest.is_empty() || rest.starts_with(['/', '\\']),
+        None => false,
+    }
+}
+
+fn normalize_path(path: &Path, project: &Project, test: &Test) -> String {
+    let replacements = project_info_replacements(project, test);
+    let replacements = replacements.iter().map(|(p, r)| (p.as_str(), r.as_str()));
+    apply_replacements(path.to_string_lossy(), replacements).into_owned()
+}
+
+/// Returns the diagnostics as (pretty-printed) JSON for snapshotting,
+/// omitting failure notes (e.g. "For more information about this error, …").
+pub(crate) fn json<'a>(
+    diagnostics: &[Diagnostic],
+    filters: &[RegexFilter],
+) -> Option<Cow<'a, str>> {
+    let diagnostics: Vec<&Diagnostic> = diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.level != DiagnosticLevel::FailureNote)
+        .collect();
+
+    if diagnostics.is_empty() {
+        return None;
+    }
+
+    let output = serde_json::to_string_pretty(&diagnostics)
+        .expect("diagnostics should be serializable as JSON");
+
+    let output = apply_regex_replacements(Cow::from(output), filters, FilterTarget::Stderr);
+
+    post_process(output)
+}
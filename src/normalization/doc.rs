@@ -0,0 +1,24 @@
+use std::borrow::Cow;
+
+use crate::{options::RegexFilter, project::Project, test::Test};
+
+pub(crate) fn stdout<'a>(
+    _input: Cow<'a, str>,
+    _project: &Project,
+    _test: &Test,
+    _filters: &[RegexFilter],
+) -> Option<Cow<'a, str>> {
+    // The stdout of `cargo doc` isn't really that interesting to us.
+    None
+}
+
+pub(crate) fn stderr<'a>(
+    input: Cow<'a, str>,
+    project: &Project,
+    test: &Test,
+    filters: &[RegexFilter],
+) -> Option<Cow<'a, str>> {
+    // Rustdoc's warnings aren't affected by `-Awarnings`
+    // (as that only gets passed to `rustc`), so we always keep them:
+    super::check::stderr(input, project, test, filters, true)
+}
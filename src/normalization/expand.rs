@@ -3,7 +3,7 @@ use std::borrow::Cow;
 use syn::{punctuated::Punctuated, Item, Meta, Token};
 
 use crate::{
-    cargo::{line_is_error, line_is_warning, line_should_be_omitted},
+    cargo::{line_is_error, line_is_status, line_is_warning, line_should_be_omitted},
     normalization::utils::{
         apply_regex_replacements, apply_replacements, post_process, project_info_replacements,
     },
@@ -30,6 +30,7 @@ pub(crate) fn stderr<'a>(
     project: &Project,
     test: &Test,
     filters: &[RegexFilter],
+    keep_warnings: bool,
 ) -> Option<Cow<'a, str>> {
     let replacements = project_info_replacements(project, test);
 
@@ -37,9 +38,10 @@ pub(crate) fn stderr<'a>(
 
     let output = trimmed_input
         .lines()
-        .skip_while(|line| !line_is_error(line))
+        .skip_while(|line| !(line_is_error(line) || (keep_warnings && line_is_warning(line))))
         .filter(|line| !line_should_be_omitted(line))
-        .filter(|line| !line_is_warning(line))
+        .filter(|line| !line_is_status(line))
+        .filter(|line| keep_warnings || !line_is_warning(line))
         .map(|line| {
             let replacements = replacements.iter().map(|(p, r)| (p.as_str(), r.as_str()));
             apply_replacements(Cow::from(line), replacements)
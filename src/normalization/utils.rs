@@ -34,11 +34,15 @@ pub(super) fn post_process(input: Cow<str>) -> Option<Cow<str>> {
     }
 }
 
-pub(super) fn project_info_replacements(project: &Project, test: &Test) -> [(String, String); 3] {
+pub(super) fn project_info_replacements(project: &Project, test: &Test) -> [(String, String); 4] {
     let bin = test.bin.clone();
     let name = project.name.clone();
+    let project_path = project.dir.to_string_lossy().into_owned();
     let src_path = project.manifest_dir.to_string_lossy().into_owned();
+    // The test project's directory contains both the project's name and (by default)
+    // the crate's directory, so it needs to get replaced before either of them:
     [
+        (project_path, "<PROJECT>".to_owned()),
         (bin, "<BIN>".to_owned()),
         (name, "<CRATE>".to_owned()),
         (src_path, "".to_owned()),
@@ -1,5 +1,6 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, path::PathBuf};
 
+use cargo_toml::{DepsSet, Profile};
 use regex::Regex;
 
 /// Target output stream for a filter
@@ -17,6 +18,28 @@ pub(crate) struct RegexFilter {
     pub replacement: String,
 }
 
+/// How test files get compiled
+#[derive(Copy, Clone, Eq, PartialEq, Default, Debug)]
+pub(crate) enum TargetKind {
+    /// As binary targets of the test project.
+    #[default]
+    Bin,
+    /// As library targets of their own packages within the test project's workspace.
+    Lib,
+}
+
+/// How to deal with compiler warnings
+#[derive(Copy, Clone, Eq, PartialEq, Default, Debug)]
+pub(crate) enum Warnings {
+    /// Suppress warnings via `-Awarnings`.
+    #[default]
+    Suppress,
+    /// Keep warnings and include them in snapshots.
+    Report,
+    /// Keep warnings and treat them as failures.
+    Deny,
+}
+
 /// Options for passing to `cargo expand`/`cargo check`.
 #[derive(Clone, Default, Debug)]
 pub(crate) struct Options {
@@ -24,8 +47,52 @@ pub(crate) struct Options {
     pub args: Vec<String>,
     // Additional env variables to pass to `cargo expand`/`cargo check`.
     pub envs: HashMap<String, String>,
+    // Features of the package under test to enable.
+    pub features: Vec<String>,
+    // Whether to enable all features of the package under test.
+    pub all_features: bool,
+    // Whether to disable the default features of the package under test.
+    pub no_default_features: bool,
+    // Whether to build test files with the `release` profile (instead of `dev`).
+    pub release: bool,
+    // Settings to apply to the profile that test files get built with.
+    pub profile: Profile,
+    // Conditional compilation options (i.e. `--cfg name` or `--cfg name="value"`) to pass to rustc/rustdoc.
+    pub cfgs: Vec<(String, Option<String>)>,
+    // Additional flags to pass to rustc.
+    pub rustflags: Vec<String>,
+    // The name of the package under test (instead of the calling one).
+    pub package: Option<String>,
+    // The manifest of the package (or workspace) under test (instead of the calling one).
+    pub manifest_path: Option<PathBuf>,
+    // Names of additional workspace members to make available to test files.
+    pub workspace_dependencies: Vec<String>,
+    // The key to declare the package under test as a dependency under (instead of its name).
+    pub crate_alias: Option<String>,
+    // Additional dependencies to make available to test files.
+    pub dependencies: DepsSet,
+    // Whether to make the package's dev-dependencies available to test files.
+    pub dev_dependencies: bool,
+    // The build script to attach to the test project.
+    pub build_script: Option<PathBuf>,
+    // Files or directories (relative to the crate under test) to copy into the test project.
+    pub fixtures: Vec<PathBuf>,
+    // Lint level flags (e.g. `-Wclippy::pedantic`) to pass to `cargo clippy`.
+    pub clippy_lints: Vec<String>,
+    // How test files get compiled.
+    pub target_kind: TargetKind,
+    // How to deal with compiler warnings.
+    pub warnings: Warnings,
+    // Whether to snapshot the compiler's structured diagnostics of `cargo check`.
+    pub json_diagnostics: bool,
+    // Whether to skip the `.err.txt` snapshots (e.g. in favor of `//~` annotations).
+    pub skip_stderr_snapshots: bool,
     // Whether to skip snapshot writing when running with `TRYEXPAND=overwrite`.
     pub skip_overwrite: bool,
     // Regex filters to apply to output before snapshot comparison.
     pub filters: Vec<RegexFilter>,
+    // Whether to pass `--locked` to all cargo commands.
+    pub locked: bool,
+    // Whether to pass `--offline` to all cargo commands.
+    pub offline: bool,
 }
@@ -1,6 +1,6 @@
 use std::{
     fs,
-    path::{Path, PathBuf},
+    path::{Component, Path, PathBuf},
 };
 
 use cargo_metadata::{Metadata, Package};
@@ -8,11 +8,43 @@ use cargo_metadata::{Metadata, Package};
 use crate::{
     cargo::{self},
     error::{Error, Result},
-    manifest,
+    manifest::{self, Auxiliary},
+    options::{Options, TargetKind},
     test::Test,
     utils,
 };
 
+/// The directory (relative to the project's directory) containing
+/// the packages for compiling test files as library targets.
+const LIBRARY_PACKAGES_DIR: &str = "libs";
+
+/// Returns the directory (relative to the project's directory)
+/// of the package for compiling the test file as a library target.
+pub(crate) fn library_package_dir(test: &Test) -> PathBuf {
+    Path::new(LIBRARY_PACKAGES_DIR).join(&test.bin)
+}
+
+/// The directory (relative to the project's directory)
+/// containing the packages of auxiliary crates.
+const AUXILIARY_PACKAGES_DIR: &str = "auxiliary";
+
+/// Returns the directory (relative to the project's directory)
+/// of the package for compiling the auxiliary crate.
+pub(crate) fn auxiliary_package_dir(auxiliary: &Auxiliary) -> PathBuf {
+    Path::new(AUXILIARY_PACKAGES_DIR).join(&auxiliary.name)
+}
+
+/// The files and directories (relative to the project's directory)
+/// that get generated for the project, which fixtures must not overwrite.
+const GENERATED_PATHS: [&str; 6] = [
+    "Cargo.toml",
+    "Cargo.lock",
+    ".cargo",
+    "lib.rs",
+    LIBRARY_PACKAGES_DIR,
+    AUXILIARY_PACKAGES_DIR,
+];
+
 #[derive(Debug)]
 pub(crate) struct Project {
     pub name: String,
@@ -28,6 +60,7 @@ impl Project {
         test_suite_id: &str,
         target_dir: &Path,
         tests: I,
+        options: &Options,
     ) -> Result<Project>
     where
         I: IntoIterator<Item = &'a Test>,
@@ -52,14 +85,41 @@ impl Project {
             name,
         };
 
-        let manifest =
-            manifest::cargo_manifest(metadata, package, &test_crate_name, &project, tests)?;
+        let tests: Vec<&Test> = tests.into_iter().collect();
+
+        let auxiliaries = manifest::auxiliaries(&test_crate_name, tests.iter().copied())?;
+
+        let mut manifest = manifest::cargo_manifest(
+            metadata,
+            package,
+            &test_crate_name,
+            &project,
+            tests.iter().copied(),
+            options,
+        )?;
+
+        // Auxiliary crates get the same dependencies as the test files,
+        // except for (other) auxiliary crates:
+        let auxiliary_manifests: Vec<_> = auxiliaries
+            .iter()
+            .map(|auxiliary| {
+                (
+                    auxiliary,
+                    manifest::auxiliary_manifest(&manifest, auxiliary),
+                )
+            })
+            .collect();
+
+        // Binaries share the test project's package (and thus its dependencies),
+        // whereas libraries only get access to the auxiliary crates they declare:
+        if options.target_kind == TargetKind::Bin {
+            manifest::add_auxiliary_dependencies(&mut manifest, &project, &auxiliaries)?;
+        }
+
         let manifest_toml =
             basic_toml::to_string(&manifest).map_err(Error::CargoManifestSerializationFailed)?;
 
-        let config = cargo::make_config();
-        let config_toml =
-            basic_toml::to_string(&config).map_err(Error::CargoConfigSerializationFailed)?;
+        let config_toml = cargo::make_config(&project, options)?.to_toml()?;
 
         if project.dir.exists() {
             // Remove remaining artifacts from previous runs if exist.
@@ -74,14 +134,115 @@ impl Project {
         utils::write(project.dir.join("Cargo.toml"), manifest_toml)?;
         utils::write(project.dir.join("lib.rs"), b"\n")?;
 
+        for (auxiliary, auxiliary_manifest) in auxiliary_manifests {
+            let auxiliary_manifest_toml = basic_toml::to_string(&auxiliary_manifest)
+                .map_err(Error::CargoManifestSerializationFailed)?;
+
+            let auxiliary_package_dir = project.dir.join(auxiliary_package_dir(auxiliary));
+            utils::create_dir_all(&auxiliary_package_dir)?;
+            utils::write(
+                auxiliary_package_dir.join("Cargo.toml"),
+                auxiliary_manifest_toml,
+            )?;
+        }
+
+        if options.target_kind == TargetKind::Lib {
+            for test in &tests {
+                let mut library_manifest = manifest::library_manifest(&manifest, test);
+                manifest::add_auxiliary_dependencies(
+                    &mut library_manifest,
+                    &project,
+                    manifest::test_auxiliaries(test, &auxiliaries),
+                )?;
+                let library_manifest_toml = basic_toml::to_string(&library_manifest)
+                    .map_err(Error::CargoManifestSerializationFailed)?;
+
+                let library_package_dir = project.dir.join(library_package_dir(test));
+                utils::create_dir_all(&library_package_dir)?;
+                utils::write(
+                    library_package_dir.join("Cargo.toml"),
+                    library_manifest_toml,
+                )?;
+            }
+        }
+
+        for fixture in &options.fixtures {
+            copy_fixture(&project, &tests, fixture, options)?;
+        }
+
+        // Seed the project with the source project's lockfile (if one exists),
+        // so that it resolves to the exact same versions of dependencies:
+        let lockfile_path = metadata.workspace_root.as_std_path().join("Cargo.lock");
+        if lockfile_path.exists() {
+            utils::copy(&lockfile_path, project.dir.join("Cargo.lock"))?;
+        } else if options.locked {
+            return Err(Error::CargoLockfileNotFound {
+                path: lockfile_path,
+            });
+        }
+
         utils::create_dir_all(&project.target_dir)?;
 
-        cargo::build_dependencies(&project)?;
+        if options.locked {
+            cargo::sync_lockfile(&project, options)?;
+        }
+
+        cargo::build_dependencies(&project, options)?;
 
         Ok(project)
     }
 }
 
+/// Copies the fixture (relative to the crate under test) to the same relative path
+/// within each package that compiles test files, so that macros resolving paths
+/// relative to `CARGO_MANIFEST_DIR` find it just like within the crate under test.
+fn copy_fixture(
+    project: &Project,
+    tests: &[&Test],
+    fixture: &Path,
+    options: &Options,
+) -> Result<()> {
+    let is_contained = fixture
+        .components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+    if !is_contained {
+        return Err(Error::InvalidFixturePath {
+            path: fixture.to_owned(),
+        });
+    }
+
+    // Fixtures get copied into the project's (or package's) directory itself,
+    // so neither the directory as a whole, nor any generated file may be replaced:
+    let is_generated = match fixture.components().find(|c| c != &Component::CurDir) {
+        Some(component) => GENERATED_PATHS
+            .iter()
+            .any(|path| component.as_os_str() == *path),
+        None => true,
+    };
+    if is_generated {
+        return Err(Error::ReservedFixturePath {
+            path: fixture.to_owned(),
+        });
+    }
+
+    let package_dirs: Vec<PathBuf> = match options.target_kind {
+        TargetKind::Bin => vec![project.dir.clone()],
+        TargetKind::Lib => tests
+            .iter()
+            .map(|test| project.dir.join(library_package_dir(test)))
+            .collect(),
+    };
+
+    for package_dir in package_dirs {
+        utils::copy_all(
+            project.manifest_dir.join(fixture),
+            package_dir.join(fixture),
+        )?;
+    }
+
+    Ok(())
+}
+
 impl Drop for Project {
     fn drop(&mut self) {
         let should_keep_artifacts = match should_keep_artifacts() {
@@ -5,8 +5,11 @@ use std::{
 };
 
 use crate::{
+    annotation::{self, Annotation, AnnotationMismatch},
     cargo::{self, CargoOutput},
-    error::Result,
+    directive::{self, Directive},
+    error::{Error, Result},
+    normalization,
     options::Options,
     project::Project,
     utils,
@@ -16,6 +19,9 @@ use crate::{
 pub(crate) enum Action {
     Expand,
     Check,
+    Clippy,
+    Doc,
+    DocTest,
     Test,
     Run,
 }
@@ -103,6 +109,9 @@ impl TestReport {
 pub(crate) enum ActionOutput {
     Expand(CargoOutput),
     Check(CargoOutput),
+    Clippy(CargoOutput),
+    Doc(CargoOutput),
+    DocTest(CargoOutput),
     Test(CargoOutput),
     Run(CargoOutput),
 }
@@ -112,6 +121,9 @@ impl ActionOutput {
         match self {
             Self::Expand(output) => output,
             Self::Check(output) => output,
+            Self::Clippy(output) => output,
+            Self::Doc(output) => output,
+            Self::DocTest(output) => output,
             Self::Test(output) => output,
             Self::Run(output) => output,
         }
@@ -121,6 +133,9 @@ impl ActionOutput {
         match self {
             Self::Expand(output) => output.evaluation,
             Self::Check(output) => output.evaluation,
+            Self::Clippy(output) => output.evaluation,
+            Self::Doc(output) => output.evaluation,
+            Self::DocTest(output) => output.evaluation,
             Self::Test(output) => output.evaluation,
             Self::Run(output) => output.evaluation,
         }
@@ -154,6 +169,7 @@ pub(crate) enum TestOutcome {
         path: PathBuf,
         content: String,
     },
+    AnnotationMismatch(AnnotationMismatch),
     UnexpectedSuccess {
         source: String,
         expanded: Option<String>,
@@ -177,6 +193,7 @@ impl TestOutcome {
             Self::SnapshotUpdated { .. } => TestStatus::Success,
             Self::SnapshotExpected { .. } => TestStatus::Failure,
             Self::SnapshotUnexpected { .. } => TestStatus::Failure,
+            Self::AnnotationMismatch(_) => TestStatus::Failure,
             Self::UnexpectedSuccess { .. } => TestStatus::Failure,
             Self::UnexpectedFailure { .. } => TestStatus::Failure,
         }
@@ -200,10 +217,106 @@ pub(crate) struct TestPlan {
 #[derive(Debug)]
 pub(crate) struct Test {
     pub bin: String,
+    // The test case's path (i.e. either a single file, or a directory).
     pub path: PathBuf,
+    // The path of the test case's crate root (i.e. `main.rs`/`lib.rs` for directories).
+    pub entry_path: PathBuf,
+    // The (canonical) paths of the auxiliary crates the test case depends on.
+    pub auxiliaries: Vec<PathBuf>,
+    // The diagnostics expected via `//~` annotations within the test case's crate root
+    // (only parsed for test suites that verify them).
+    pub annotations: Vec<Annotation>,
 }
 
 impl Test {
+    /// Names of the files that are recognized as crate roots of directory test cases.
+    pub(crate) const DIRECTORY_ENTRIES: [&'static str; 2] = ["main.rs", "lib.rs"];
+
+    pub(crate) fn new(bin: String, path: PathBuf) -> Result<Self> {
+        let entry_path = if path.is_dir() {
+            Self::DIRECTORY_ENTRIES
+                .iter()
+                .map(|entry| path.join(entry))
+                .find(|entry_path| entry_path.is_file())
+                .ok_or_else(|| Error::TestEntryNotFound { path: path.clone() })?
+        } else {
+            path.clone()
+        };
+
+        let source = String::from_utf8_lossy(&utils::read(&entry_path)?).into_owned();
+
+        // Auxiliary crates are declared relative to the test file's directory:
+        let entry_dir = entry_path.parent().unwrap_or(Path::new(""));
+        let auxiliaries = directive::parse_directives(&source)?
+            .into_iter()
+            .map(|directive| match directive {
+                Directive::Aux(path) => utils::canonicalize(entry_dir.join(path)),
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self {
+            bin,
+            path,
+            entry_path,
+            auxiliaries,
+            annotations: vec![],
+        })
+    }
+
+    /// Parses the test case's annotations if they get verified (i.e. by `cargo check`),
+    /// or otherwise makes sure that it has none, which would silently get ignored.
+    ///
+    /// Only the crate root may contain annotations, as the diagnostics only get
+    /// matched against it, so other files of directory test cases must not have any.
+    pub(crate) fn load_annotations(&mut self, verify: bool) -> Result<()> {
+        if self.path.is_dir() {
+            for path in source_files(&self.path)? {
+                if path == self.entry_path {
+                    continue;
+                }
+                let source = String::from_utf8_lossy(&utils::read(&path)?).into_owned();
+                if annotation::contains_annotations(&source) {
+                    return Err(Error::MisplacedAnnotations { path });
+                }
+            }
+        }
+
+        let source = String::from_utf8_lossy(&utils::read(&self.entry_path)?).into_owned();
+
+        if verify {
+            self.annotations = annotation::parse_annotations(&source)?;
+        } else if annotation::contains_annotations(&source) {
+            return Err(Error::UnverifiedAnnotations {
+                path: self.entry_path.clone(),
+            });
+        }
+
+        Ok(())
+    }
+
+    // Test paths are relative to the current directory,
+    // just like the glob patterns they got matched by:
+    pub(crate) fn absolute_entry_path(&self) -> PathBuf {
+        std::env::current_dir().unwrap().join(&self.entry_path)
+    }
+
+    pub(crate) fn absolute_path(&self) -> PathBuf {
+        std::env::current_dir().unwrap().join(&self.path)
+    }
+
+    // Snapshots of single file test cases replace the file's extension,
+    // while those of directory test cases get placed next to the directory:
+    fn snapshot_path(&self, suffix: &str) -> PathBuf {
+        if self.path == self.entry_path {
+            self.path.with_extension(suffix)
+        } else {
+            let mut path = self.path.clone().into_os_string();
+            path.push(".");
+            path.push(suffix);
+            PathBuf::from(path)
+        }
+    }
+
     pub fn run(
         &mut self,
         plan: &TestPlan,
@@ -245,6 +358,9 @@ impl Test {
         let action_output = match action {
             Action::Expand => ActionOutput::Expand(cargo::expand(project, self, options)?),
             Action::Check => ActionOutput::Check(cargo::check(project, self, options)?),
+            Action::Clippy => ActionOutput::Clippy(cargo::clippy(project, self, options)?),
+            Action::Doc => ActionOutput::Doc(cargo::doc(project, self, options)?),
+            Action::DocTest => ActionOutput::DocTest(cargo::doc_test(project, self, options)?),
             Action::Test => ActionOutput::Test(cargo::test(project, self, options)?),
             Action::Run => ActionOutput::Run(cargo::run(project, self, options)?),
         };
@@ -254,6 +370,11 @@ impl Test {
                 let post_action = match post_action {
                     Action::Expand => panic!("unexpected `expand` as post-action"),
                     Action::Check => ActionOutput::Check(cargo::check(project, self, options)?),
+                    Action::Clippy => ActionOutput::Clippy(cargo::clippy(project, self, options)?),
+                    Action::Doc => ActionOutput::Doc(cargo::doc(project, self, options)?),
+                    Action::DocTest => {
+                        ActionOutput::DocTest(cargo::doc_test(project, self, options)?)
+                    }
                     Action::Test => ActionOutput::Test(cargo::test(project, self, options)?),
                     Action::Run => ActionOutput::Run(cargo::run(project, self, options)?),
                 };
@@ -270,7 +391,7 @@ impl Test {
             post_action: post_action_output,
         };
 
-        let source = String::from_utf8_lossy(&utils::read(&self.path)?).into_owned();
+        let source = String::from_utf8_lossy(&utils::read(&self.entry_path)?).into_owned();
 
         let evaluation = match (report.evaluation(), expectation) {
             (TestStatus::Success, TestStatus::Failure) => {
@@ -283,7 +404,7 @@ impl Test {
             }
             (TestStatus::Success, TestStatus::Success)
             | (TestStatus::Failure, TestStatus::Failure) => {
-                self.process_snapshots(&report, behavior, observe)?
+                self.process_snapshots(&report, behavior, project, options, observe)?
             }
         };
 
@@ -294,11 +415,23 @@ impl Test {
         &mut self,
         report: &TestReport,
         behavior: TestBehavior,
+        project: &Project,
+        options: &Options,
         observe: &mut dyn FnMut(TestOutcome),
     ) -> Result<TestStatus> {
-        let expanded_snapshot_path = self.path.with_extension(crate::OUT_RS_FILE_SUFFIX);
-        let output_snapshot_path = self.path.with_extension(crate::OUT_TXT_FILE_SUFFIX);
-        let error_snapshot_path = self.path.with_extension(crate::ERR_TXT_FILE_SUFFIX);
+        let expanded_snapshot_path = self.snapshot_path(crate::OUT_RS_FILE_SUFFIX);
+        let output_snapshot_path = self.snapshot_path(crate::OUT_TXT_FILE_SUFFIX);
+        let error_snapshot_path = self.snapshot_path(crate::ERR_TXT_FILE_SUFFIX);
+        let diagnostics_snapshot_path = self.snapshot_path(crate::ERR_JSON_FILE_SUFFIX);
+
+        let diagnostics_json = |output: &CargoOutput| {
+            if options.json_diagnostics {
+                normalization::diagnostics_json(&output.diagnostics, &options.filters)
+                    .map(|cow| cow.into_owned())
+            } else {
+                None
+            }
+        };
 
         if let Some(post_action) = &report.post_action {
             assert!(
@@ -317,11 +450,20 @@ impl Test {
         match &report.action {
             ActionOutput::Expand(output) => {
                 snapshots.push((&expanded_snapshot_path, output.stdout.clone()));
+                // If the expansion succeeds these are left to the post-action (if any):
+                if report.action.evaluation() == TestStatus::Failure {
+                    snapshots.push((&diagnostics_snapshot_path, diagnostics_json(output)));
+                }
             }
             ActionOutput::Check(output) => {
+                snapshots.push((&output_snapshot_path, output.stdout.clone()));
                 snapshots.push((&error_snapshot_path, output.stderr.clone()));
+                snapshots.push((&diagnostics_snapshot_path, diagnostics_json(output)));
             }
-            ActionOutput::Test(output) => {
+            ActionOutput::Clippy(output) | ActionOutput::Doc(output) => {
+                snapshots.push((&error_snapshot_path, output.stderr.clone()));
+            }
+            ActionOutput::Test(output) | ActionOutput::DocTest(output) => {
                 snapshots.push((&output_snapshot_path, output.stdout.clone()));
                 snapshots.push((&error_snapshot_path, output.stderr.clone()));
             }
@@ -342,9 +484,14 @@ impl Test {
                             unreachable!("`expand` should not be accessible as a post-action")
                         }
                         ActionOutput::Check(output) => {
+                            snapshots.push((&output_snapshot_path, output.stdout.clone()));
                             snapshots.push((&error_snapshot_path, output.stderr.clone()));
+                            snapshots.push((&diagnostics_snapshot_path, diagnostics_json(output)));
                         }
-                        ActionOutput::Test(output) => {
+                        ActionOutput::Clippy(output) | ActionOutput::Doc(output) => {
+                            snapshots.push((&error_snapshot_path, output.stderr.clone()));
+                        }
+                        ActionOutput::Test(output) | ActionOutput::DocTest(output) => {
                             snapshots.push((&output_snapshot_path, output.stdout.clone()));
                             snapshots.push((&error_snapshot_path, output.stderr.clone()));
                         }
@@ -357,11 +504,35 @@ impl Test {
             }
         }
 
-        self.evaluate_snapshots(snapshots, behavior, observe)?;
+        if options.skip_stderr_snapshots {
+            snapshots.retain(|(path, _)| *path != &error_snapshot_path);
+        }
+
+        let mut outcomes = self.evaluate_snapshots(snapshots, behavior)?;
+
+        // Annotations need to be verified before reporting any outcomes,
+        // so that a mismatch doesn't follow matching snapshots reported as ok:
+        outcomes.extend(self.evaluate_annotations(report, project));
+
+        Self::report_outcomes(outcomes, observe);
 
         Ok(report.evaluation())
     }
 
+    // Annotations get verified against the diagnostics of `cargo check`,
+    // which is the only action that provides structured diagnostics:
+    fn evaluate_annotations(&self, report: &TestReport, project: &Project) -> Option<TestOutcome> {
+        let output = match (&report.action, &report.post_action) {
+            (ActionOutput::Check(output), _) | (_, Some(ActionOutput::Check(output))) => output,
+            _ => return None,
+        };
+
+        let file = normalization::diagnostics_file(project, self);
+
+        annotation::verify(&self.annotations, &output.diagnostics, &file)
+            .map(TestOutcome::AnnotationMismatch)
+    }
+
     fn report_unexpected_success(
         &mut self,
         source: &str,
@@ -428,8 +599,7 @@ impl Test {
         &mut self,
         snapshots: Vec<(&PathBuf, Option<String>)>,
         behavior: TestBehavior,
-        observe: &mut dyn FnMut(TestOutcome),
-    ) -> Result<TestStatus> {
+    ) -> Result<Vec<TestOutcome>> {
         let mut outcomes = vec![];
 
         for (snapshot_path, actual) in snapshots {
@@ -455,6 +625,11 @@ impl Test {
             }
         }
 
+        Ok(outcomes)
+    }
+
+    // Only failures get reported, if there are any:
+    fn report_outcomes(outcomes: Vec<TestOutcome>, observe: &mut dyn FnMut(TestOutcome)) {
         let (successes, failures): (Vec<_>, Vec<_>) = outcomes
             .into_iter()
             .partition(|outcome| outcome.as_status() == TestStatus::Success);
@@ -463,14 +638,12 @@ impl Test {
             for outcome in failures {
                 observe(outcome);
             }
-            return Ok(TestStatus::Failure);
+            return;
         }
 
         for outcome in successes {
             observe(outcome);
         }
-
-        Ok(TestStatus::Success)
     }
 
     fn evaluate_snapshot_overwriting_files(
@@ -547,3 +720,18 @@ impl Test {
         }
     }
 }
+
+// Returns the paths of the Rust source files within the directory (and its subdirectories):
+fn source_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut paths = vec![];
+
+    for path in utils::read_dir(dir)? {
+        if path.is_dir() {
+            paths.extend(source_files(&path)?);
+        } else if path.extension().is_some_and(|extension| extension == "rs") {
+            paths.push(path);
+        }
+    }
+
+    Ok(paths)
+}
@@ -1,7 +1,7 @@
 use core::panic;
 use std::{
     collections::hash_map::DefaultHasher,
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     env,
     fmt::Write,
     hash::{Hash as _, Hasher as _},
@@ -9,12 +9,15 @@ use std::{
     path::{Path, PathBuf},
 };
 
+use cargo_metadata::{Metadata, Package};
+use cargo_toml::{Dependency, DependencyDetail, Value};
+
 use crate::{
     error::{Error, Result},
     message,
-    options::Options,
+    options::{Options, TargetKind, Warnings},
     project::Project,
-    test::{Action, Test, TestBehavior, TestPlan, TestStatus},
+    test::{Action, Test, TestBehavior, TestOutcome, TestPlan, TestStatus},
     TRYEXPAND_ENV_KEY, TRYEXPAND_ENV_VAL_EXPECT, TRYEXPAND_ENV_VAL_OVERWRITE,
 };
 
@@ -64,6 +67,9 @@ pub struct ExpandTestSuite(pub(crate) TestSuite);
 impl ExpandTestSuite {
     /// Adds a single argument to pass to `cargo expand`.
     ///
+    /// Note that the expansion's diagnostics get obtained via `--message-format=json`,
+    /// so passing `--message-format` panics.
+    ///
     /// # Examples
     ///
     /// ```no_run
@@ -80,6 +86,9 @@ impl ExpandTestSuite {
 
     /// Adds multiple arguments to pass to `cargo expand`.
     ///
+    /// Note that the expansion's diagnostics get obtained via `--message-format=json`,
+    /// so passing `--message-format` panics.
+    ///
     /// # Examples
     ///
     /// ```no_run
@@ -130,266 +139,1283 @@ impl ExpandTestSuite {
         Self(self.0.envs(envs))
     }
 
-    /// Prevents overwriting existing snapshot files even when `TRYEXPAND=overwrite` is set.
+    /// Builds test files (and their dependencies) with the `release` profile,
+    /// instead of the `dev` profile.
     ///
-    /// This is useful when you want to preserve specific snapshots while allowing
-    /// others to be updated during test runs.
+    /// # Examples
+    ///
+    /// ```no_run
+    /// tryexpand::expand(["tests/*.rs"])
+    ///     .release()
+    ///     .expect_pass();
+    /// ```
+    pub fn release(self) -> Self {
+        Self(self.0.release())
+    }
+
+    /// Sets the `opt-level` (e.g. `"0"`, `"3"`, `"s"` or `"z"`)
+    /// of the profile that test files get built with.
     ///
     /// # Examples
     ///
     /// ```no_run
     /// tryexpand::expand(["tests/*.rs"])
-    ///     .skip_overwrite()
+    ///     .opt_level("3")
     ///     .expect_pass();
     /// ```
-    pub fn skip_overwrite(self) -> Self {
-        Self(self.0.skip_overwrite())
+    pub fn opt_level<T>(self, level: T) -> Self
+    where
+        T: AsRef<str>,
+    {
+        Self(self.0.opt_level(level))
     }
 
-    /// Applies a regex filter to normalize stdout output before snapshotting.
+    /// Enables (or disables) `debug-assertions`
+    /// for the profile that test files get built with.
     ///
-    /// This is useful for removing non-deterministic content like timestamps or paths
-    /// from the expanded output.
+    /// # Examples
     ///
-    /// # Panics
+    /// ```no_run
+    /// tryexpand::expand(["tests/*.rs"])
+    ///     .debug_assertions(false)
+    ///     .expect_pass();
+    /// ```
+    pub fn debug_assertions(self, enabled: bool) -> Self {
+        Self(self.0.debug_assertions(enabled))
+    }
+
+    /// Enables (or disables) `overflow-checks`
+    /// for the profile that test files get built with.
     ///
-    /// Panics if the regex pattern is invalid.
+    /// # Examples
+    ///
+    /// ```no_run
+    /// tryexpand::expand(["tests/*.rs"])
+    ///     .overflow_checks(false)
+    ///     .expect_pass();
+    /// ```
+    pub fn overflow_checks(self, enabled: bool) -> Self {
+        Self(self.0.overflow_checks(enabled))
+    }
+
+    /// Sets `panic = "abort"` for the profile that test files get built with.
+    ///
+    /// Note that cargo ignores this setting for `cargo test`.
     ///
     /// # Examples
     ///
     /// ```no_run
     /// tryexpand::expand(["tests/*.rs"])
-    ///     .filter_stdout(r"/path/to/\S+", "/path/to/file")
+    ///     .panic_abort()
     ///     .expect_pass();
     /// ```
-    pub fn filter_stdout<P, R>(self, pattern: P, replacement: R) -> Self
+    pub fn panic_abort(self) -> Self {
+        Self(self.0.panic_abort())
+    }
+
+    /// Enables the given features of the crate under test
+    /// (for all cargo commands, including the prebuild of dependencies).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// tryexpand::expand(["tests/*.rs"])
+    ///     .features(["my-feature"])
+    ///     .expect_pass();
+    /// ```
+    pub fn features<T, I>(self, features: I) -> Self
     where
-        P: AsRef<str>,
-        R: AsRef<str>,
+        T: AsRef<str>,
+        I: IntoIterator<Item = T>,
     {
-        Self(self.0.filter_stdout(pattern, replacement))
+        Self(self.0.features(features))
     }
 
-    /// Applies a regex filter to normalize stderr output before snapshotting.
+    /// Enables all features of the crate under test
+    /// (for all cargo commands, including the prebuild of dependencies).
     ///
-    /// This is useful for removing non-deterministic content like timestamps or paths
-    /// from error messages.
+    /// # Examples
     ///
-    /// # Panics
+    /// ```no_run
+    /// tryexpand::expand(["tests/*.rs"])
+    ///     .all_features()
+    ///     .expect_pass();
+    /// ```
+    pub fn all_features(self) -> Self {
+        Self(self.0.all_features())
+    }
+
+    /// Disables the default features of the crate under test
+    /// (for all cargo commands, including the prebuild of dependencies).
     ///
-    /// Panics if the regex pattern is invalid.
+    /// # Examples
+    ///
+    /// ```no_run
+    /// tryexpand::expand(["tests/*.rs"])
+    ///     .no_default_features()
+    ///     .features(["my-feature"])
+    ///     .expect_pass();
+    /// ```
+    pub fn no_default_features(self) -> Self {
+        Self(self.0.no_default_features())
+    }
+
+    /// Enables the given `--cfg` option for test files (and their dependencies),
+    /// e.g. for testing `#[cfg(my_cfg)]`-gated macro branches.
+    ///
+    /// The option also gets declared via `--check-cfg`, so it doesn't trigger
+    /// `unexpected_cfgs` warnings.
     ///
     /// # Examples
     ///
     /// ```no_run
     /// tryexpand::expand(["tests/*.rs"])
-    ///     .filter_stderr(r"thread '\S+'", "thread 'test'")
+    ///     .cfg("my_cfg")
     ///     .expect_pass();
     /// ```
-    pub fn filter_stderr<P, R>(self, pattern: P, replacement: R) -> Self
+    pub fn cfg<T>(self, name: T) -> Self
     where
-        P: AsRef<str>,
-        R: AsRef<str>,
+        T: AsRef<str>,
     {
-        Self(self.0.filter_stderr(pattern, replacement))
+        Self(self.0.cfg(name))
     }
 
-    /// Adds a type-checking step after macro expansion.
+    /// Enables the given key-value `--cfg` option (i.e. `name="value"`) for test files
+    /// (and their dependencies), e.g. for testing `#[cfg(backend = "x")]`-gated macro branches.
     ///
-    /// After expanding macros, this will run `cargo check` on the expanded code
-    /// and snapshot the compiler output.
+    /// # Examples
+    ///
+    /// ```no_run
+    /// tryexpand::expand(["tests/*.rs"])
+    ///     .cfg_value("backend", "x")
+    ///     .expect_pass();
+    /// ```
+    pub fn cfg_value<N, V>(self, name: N, value: V) -> Self
+    where
+        N: AsRef<str>,
+        V: AsRef<str>,
+    {
+        Self(self.0.cfg_value(name, value))
+    }
+
+    /// Passes additional flags to `rustc` for test files (and their dependencies).
+    ///
+    /// Unlike passing `RUSTFLAGS` via `.env(…)`/`.envs(…)`, these get passed
+    /// consistently to all cargo commands (including the prebuild of dependencies),
+    /// in addition to the flags of the environment and the cargo config.
     ///
     /// # Examples
     ///
     /// ```no_run
     /// tryexpand::expand(["tests/*.rs"])
-    ///     .and_check()
+    ///     .rustflags(["-Ctarget-cpu=native"])
     ///     .expect_pass();
     /// ```
-    pub fn and_check(self) -> BuildTestSuite {
-        BuildTestSuite(self.0.and_check())
+    pub fn rustflags<T, I>(self, rustflags: I) -> Self
+    where
+        T: AsRef<str>,
+        I: IntoIterator<Item = T>,
+    {
+        Self(self.0.rustflags(rustflags))
     }
 
-    /// Adds a run step after macro expansion.
+    /// Selects the package under test by name (e.g. a member of the current workspace),
+    /// instead of the calling package.
     ///
-    /// After expanding macros, this will run `cargo run` on the expanded code
-    /// and snapshot the program output.
+    /// # Examples
+    ///
+    /// ```no_run
+    /// tryexpand::expand(["tests/*.rs"])
+    ///     .package("my-derive")
+    ///     .expect_pass();
+    /// ```
+    pub fn package<T>(self, name: T) -> Self
+    where
+        T: AsRef<str>,
+    {
+        Self(self.0.package(name))
+    }
+
+    /// Selects the package under test by the path of its manifest
+    /// (relative to the current directory, i.e. the calling package's),
+    /// instead of the calling package.
+    ///
+    /// If the manifest belongs to a virtual workspace, then the package
+    /// has to be selected via [`package()`](Self::package) additionally.
     ///
     /// # Examples
     ///
     /// ```no_run
     /// tryexpand::expand(["tests/*.rs"])
-    ///     .and_run()
+    ///     .manifest_path("my-derive/Cargo.toml")
     ///     .expect_pass();
     /// ```
-    pub fn and_run(self) -> BuildTestSuite {
-        BuildTestSuite(self.0.and_run())
+    pub fn manifest_path<P>(self, path: P) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        Self(self.0.manifest_path(path))
     }
 
-    /// Adds a test execution step after macro expansion.
+    /// Adds another member of the workspace as a dependency, which test files can then use.
     ///
-    /// After expanding macros, this will run `cargo test` on the expanded code
-    /// and snapshot the test output.
+    /// This is useful for testing macros together with their runtime crates, for example.
     ///
     /// # Examples
     ///
     /// ```no_run
     /// tryexpand::expand(["tests/*.rs"])
-    ///     .and_run_tests()
+    ///     .workspace_dependency("my-runtime")
     ///     .expect_pass();
     /// ```
-    pub fn and_run_tests(self) -> BuildTestSuite {
-        BuildTestSuite(self.0.and_run_tests())
+    pub fn workspace_dependency<T>(self, name: T) -> Self
+    where
+        T: AsRef<str>,
+    {
+        Self(self.0.workspace_dependency(name))
     }
 
-    /// Expects all tests to pass (macro expansion succeeds).
+    /// Makes the crate under test available to test files under the given alias,
+    /// as if it had been declared as `alias = { package = "…" }` by its users.
     ///
-    /// This consumes the test suite and executes all tests, expecting successful
-    /// macro expansion and matching snapshots.
+    /// This is useful for catching hard-coded paths (e.g. `::my_crate::…`)
+    /// in macro expansions, which break for users who renamed the dependency.
     ///
-    /// # Panics
+    /// # Examples
     ///
-    /// Panics if any test fails or if snapshots don't match.
+    /// ```no_run
+    /// tryexpand::expand(["tests/*.rs"])
+    ///     .crate_alias("renamed")
+    ///     .expect_pass();
+    /// ```
+    pub fn crate_alias<T>(self, alias: T) -> Self
+    where
+        T: AsRef<str>,
+    {
+        Self(self.0.crate_alias(alias))
+    }
+
+    /// Adds a dependency from crates.io, which test files can then use.
+    ///
+    /// This is useful for test files that depend on helper crates
+    /// which aren't dependencies of the crate under test.
     ///
     /// # Examples
     ///
     /// ```no_run
-    /// #[test]
-    /// fn pass() {
-    ///     tryexpand::expand(["tests/pass/*.rs"])
-    ///         .expect_pass();
-    /// }
+    /// tryexpand::expand(["tests/*.rs"])
+    ///     .dependency("serde_json", "1")
+    ///     .expect_pass();
     /// ```
-    pub fn expect_pass(self) -> TestSuitePass {
-        self.0.expect_pass()
+    pub fn dependency<N, V>(self, name: N, version: V) -> Self
+    where
+        N: AsRef<str>,
+        V: AsRef<str>,
+    {
+        Self(self.0.dependency(name, version))
     }
 
-    /// Expects all tests to fail (macro expansion produces errors).
+    /// Adds a dependency from a local path, which test files can then use.
     ///
-    /// This consumes the test suite and executes all tests, expecting macro expansion
-    /// to fail and produce error output that matches the snapshots.
+    /// Relative paths are resolved against the crate under test's directory.
     ///
-    /// # Panics
+    /// # Examples
     ///
-    /// Panics if any test unexpectedly passes or if error snapshots don't match.
+    /// ```no_run
+    /// tryexpand::expand(["tests/*.rs"])
+    ///     .path_dependency("helper", "tests/helper")
+    ///     .expect_pass();
+    /// ```
+    pub fn path_dependency<N, P>(self, name: N, path: P) -> Self
+    where
+        N: AsRef<str>,
+        P: AsRef<Path>,
+    {
+        Self(self.0.path_dependency(name, path))
+    }
+
+    /// Attaches the given build script (relative to the crate under test)
+    /// to the test project, e.g. for testing macros that `include!` code
+    /// generated into `OUT_DIR`.
+    ///
+    /// The build script has access to the crate under test's build-dependencies.
+    /// Its `cargo:warning=…` output gets snapshotted by `tryexpand::check(…)`.
     ///
     /// # Examples
     ///
     /// ```no_run
-    /// #[test]
-    /// fn fail() {
-    ///     tryexpand::expand(["tests/fail/*.rs"])
-    ///         .expect_fail();
-    /// }
+    /// tryexpand::expand(["tests/*.rs"])
+    ///     .build_script("tests/support/build.rs")
+    ///     .expect_pass();
     /// ```
-    pub fn expect_fail(self) -> TestSuiteFail {
-        self.0.expect_fail()
+    pub fn build_script<P>(self, path: P) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        Self(self.0.build_script(path))
     }
-}
 
-/// A test suite builder for build/run/test operations created by
-/// [`crate::check`], [`crate::run`], [`crate::run_tests`], or by calling
-/// [`ExpandTestSuite::and_check`], [`ExpandTestSuite::and_run`], or
-/// [`ExpandTestSuite::and_run_tests`].
-///
-/// This type provides methods to configure how tests are run before calling
-/// [`expect_pass`](Self::expect_pass) or [`expect_fail`](Self::expect_fail) to execute the tests.
-///
-/// # Examples
-///
-/// ```no_run
-/// #[test]
-/// fn test_check() {
-///     tryexpand::check(["tests/check/*.rs"])
-///         .args(["--features", "test-feature"])
-///         .expect_pass();
-/// }
-/// ```
-pub struct BuildTestSuite(pub(crate) TestSuite);
+    /// Copies the given files or directories (relative to the crate under test)
+    /// to the same relative paths within the test project.
+    ///
+    /// This is useful for macros that read files relative to `CARGO_MANIFEST_DIR`,
+    /// which points to the test project, rather than the crate under test.
+    ///
+    /// Fixtures must not replace the test project as a whole (e.g. `"."`),
+    /// nor its generated files (e.g. `Cargo.toml` or `.cargo/config.toml`).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// tryexpand::expand(["tests/*.rs"])
+    ///     .fixtures(["tests/data", "config.toml"])
+    ///     .expect_pass();
+    /// ```
+    pub fn fixtures<P, I>(self, paths: I) -> Self
+    where
+        P: AsRef<Path>,
+        I: IntoIterator<Item = P>,
+    {
+        Self(self.0.fixtures(paths))
+    }
+
+    /// Makes the crate under test's dev-dependencies available to test files.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// tryexpand::expand(["tests/*.rs"])
+    ///     .with_dev_dependencies()
+    ///     .expect_pass();
+    /// ```
+    pub fn with_dev_dependencies(self) -> Self {
+        Self(self.0.with_dev_dependencies())
+    }
+
+    /// Compiles the test files as library targets (rather than binaries),
+    /// which allows for them to omit `fn main()` and use `#![no_std]`.
+    ///
+    /// The libraries are named after their file's stem (e.g. `tests/foo.rs` becomes `foo`).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// tryexpand::expand(["tests/*.rs"])
+    ///     .as_lib()
+    ///     .expect_pass();
+    /// ```
+    #[allow(clippy::wrong_self_convention)]
+    pub fn as_lib(self) -> Self {
+        Self(self.0.as_lib())
+    }
+
+    /// Keeps compiler warnings (which are suppressed via `-Awarnings` by default)
+    /// and includes them in the `.err.txt` snapshots.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// tryexpand::expand(["tests/*.rs"])
+    ///     .with_warnings()
+    ///     .expect_pass();
+    /// ```
+    pub fn with_warnings(self) -> Self {
+        Self(self.0.with_warnings())
+    }
+
+    /// Additionally snapshots the compiler's structured diagnostics of the expansion
+    /// (or, if it succeeds, of [`and_check()`](Self::and_check))
+    /// (i.e. their levels, codes, messages and spans) as `.err.json`,
+    /// which is less sensitive to changes in how rustc renders them.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// tryexpand::expand(["tests/*.rs"])
+    ///     .and_check()
+    ///     .with_json_diagnostics()
+    ///     .expect_fail();
+    /// ```
+    pub fn with_json_diagnostics(self) -> Self {
+        Self(self.0.with_json_diagnostics())
+    }
+
+    /// Skips the `.err.txt` snapshots, e.g. for relying on `//~ ERROR …`
+    /// annotations within test files instead, which are less sensitive
+    /// to changes in how rustc renders its diagnostics.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// tryexpand::expand(["tests/*.rs"])
+    ///     .without_stderr_snapshots()
+    ///     .expect_fail();
+    /// ```
+    pub fn without_stderr_snapshots(self) -> Self {
+        Self(self.0.without_stderr_snapshots())
+    }
+
+    /// Like [`with_warnings()`](Self::with_warnings), but additionally
+    /// treats any emitted warnings as test failures.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// tryexpand::expand(["tests/*.rs"])
+    ///     .deny_warnings()
+    ///     .expect_pass();
+    /// ```
+    pub fn deny_warnings(self) -> Self {
+        Self(self.0.deny_warnings())
+    }
+
+    /// Prevents overwriting existing snapshot files even when `TRYEXPAND=overwrite` is set.
+    ///
+    /// This is useful when you want to preserve specific snapshots while allowing
+    /// others to be updated during test runs.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// tryexpand::expand(["tests/*.rs"])
+    ///     .skip_overwrite()
+    ///     .expect_pass();
+    /// ```
+    pub fn skip_overwrite(self) -> Self {
+        Self(self.0.skip_overwrite())
+    }
+
+    /// Seeds the test project with the source project's `Cargo.lock`
+    /// and passes `--locked` to all cargo commands.
+    ///
+    /// This ensures that the tests use the exact same dependency versions
+    /// as the source project, failing if the lockfile is missing or stale.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// tryexpand::expand(["tests/*.rs"])
+    ///     .locked()
+    ///     .expect_pass();
+    /// ```
+    pub fn locked(self) -> Self {
+        Self(self.0.locked())
+    }
+
+    /// Passes `--offline` to all cargo commands.
+    ///
+    /// This prevents cargo from accessing the network,
+    /// requiring all dependencies to be available locally.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// tryexpand::expand(["tests/*.rs"])
+    ///     .offline()
+    ///     .expect_pass();
+    /// ```
+    pub fn offline(self) -> Self {
+        Self(self.0.offline())
+    }
+
+    /// Applies a regex filter to normalize stdout output before snapshotting.
+    ///
+    /// This is useful for removing non-deterministic content like timestamps or paths
+    /// from the expanded output.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the regex pattern is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// tryexpand::expand(["tests/*.rs"])
+    ///     .filter_stdout(r"/path/to/\S+", "/path/to/file")
+    ///     .expect_pass();
+    /// ```
+    pub fn filter_stdout<P, R>(self, pattern: P, replacement: R) -> Self
+    where
+        P: AsRef<str>,
+        R: AsRef<str>,
+    {
+        Self(self.0.filter_stdout(pattern, replacement))
+    }
+
+    /// Applies a regex filter to normalize stderr output before snapshotting.
+    ///
+    /// This is useful for removing non-deterministic content like timestamps or paths
+    /// from error messages.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the regex pattern is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// tryexpand::expand(["tests/*.rs"])
+    ///     .filter_stderr(r"thread '\S+'", "thread 'test'")
+    ///     .expect_pass();
+    /// ```
+    pub fn filter_stderr<P, R>(self, pattern: P, replacement: R) -> Self
+    where
+        P: AsRef<str>,
+        R: AsRef<str>,
+    {
+        Self(self.0.filter_stderr(pattern, replacement))
+    }
+
+    /// Adds a type-checking step after macro expansion.
+    ///
+    /// After expanding macros, this will run `cargo check` on the expanded code
+    /// and snapshot the compiler output.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// tryexpand::expand(["tests/*.rs"])
+    ///     .and_check()
+    ///     .expect_pass();
+    /// ```
+    pub fn and_check(self) -> BuildTestSuite {
+        BuildTestSuite(self.0.and_check())
+    }
+
+    /// Adds a linting step after macro expansion.
+    ///
+    /// After expanding macros, this will run `cargo clippy` on the expanded code
+    /// and snapshot the emitted lints.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// tryexpand::expand(["tests/*.rs"])
+    ///     .and_clippy()
+    ///     .expect_pass();
+    /// ```
+    pub fn and_clippy(self) -> BuildTestSuite {
+        BuildTestSuite(self.0.and_clippy())
+    }
+
+    /// Adds a documentation step after macro expansion.
+    ///
+    /// After expanding macros, this will run `cargo doc` on the expanded code
+    /// and snapshot the emitted rustdoc warnings (e.g. broken intra-doc links).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// tryexpand::expand(["tests/*.rs"])
+    ///     .and_doc()
+    ///     .expect_pass();
+    /// ```
+    pub fn and_doc(self) -> BuildTestSuite {
+        BuildTestSuite(self.0.and_doc())
+    }
+
+    /// Adds a run step after macro expansion.
+    ///
+    /// After expanding macros, this will run `cargo run` on the expanded code
+    /// and snapshot the program output.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// tryexpand::expand(["tests/*.rs"])
+    ///     .and_run()
+    ///     .expect_pass();
+    /// ```
+    pub fn and_run(self) -> BuildTestSuite {
+        BuildTestSuite(self.0.and_run())
+    }
+
+    /// Adds a test execution step after macro expansion.
+    ///
+    /// After expanding macros, this will run `cargo test` on the expanded code
+    /// and snapshot the test output.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// tryexpand::expand(["tests/*.rs"])
+    ///     .and_run_tests()
+    ///     .expect_pass();
+    /// ```
+    pub fn and_run_tests(self) -> BuildTestSuite {
+        BuildTestSuite(self.0.and_run_tests())
+    }
+
+    /// Expects all tests to pass (macro expansion succeeds).
+    ///
+    /// This consumes the test suite and executes all tests, expecting successful
+    /// macro expansion and matching snapshots.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any test fails or if snapshots don't match.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #[test]
+    /// fn pass() {
+    ///     tryexpand::expand(["tests/pass/*.rs"])
+    ///         .expect_pass();
+    /// }
+    /// ```
+    pub fn expect_pass(self) -> TestSuitePass {
+        self.0.expect_pass()
+    }
+
+    /// Expects all tests to fail (macro expansion produces errors).
+    ///
+    /// This consumes the test suite and executes all tests, expecting macro expansion
+    /// to fail and produce error output that matches the snapshots.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any test unexpectedly passes or if error snapshots don't match.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #[test]
+    /// fn fail() {
+    ///     tryexpand::expand(["tests/fail/*.rs"])
+    ///         .expect_fail();
+    /// }
+    /// ```
+    pub fn expect_fail(self) -> TestSuiteFail {
+        self.0.expect_fail()
+    }
+}
+
+/// A test suite builder for build/run/test operations created by
+/// [`crate::check`], [`crate::clippy`], [`crate::doc`], [`crate::run`], [`crate::run_tests`],
+/// or by calling [`ExpandTestSuite::and_check`], [`ExpandTestSuite::and_clippy`],
+/// [`ExpandTestSuite::and_doc`], [`ExpandTestSuite::and_run`], or [`ExpandTestSuite::and_run_tests`].
+///
+/// This type provides methods to configure how tests are run before calling
+/// [`expect_pass`](Self::expect_pass) or [`expect_fail`](Self::expect_fail) to execute the tests.
+///
+/// # Examples
+///
+/// ```no_run
+/// #[test]
+/// fn test_check() {
+///     tryexpand::check(["tests/check/*.rs"])
+///         .args(["--features", "test-feature"])
+///         .expect_pass();
+/// }
+/// ```
+pub struct BuildTestSuite(pub(crate) TestSuite);
+
+impl BuildTestSuite {
+    /// Adds a single argument to pass to the cargo command (`check`, `run`, or `test`).
+    ///
+    /// Note that `cargo check`, `cargo clippy` and `cargo doc` get run with
+    /// `--message-format=json`, so passing `--message-format` to them panics.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// tryexpand::check(["tests/*.rs"])
+    ///     .arg("--verbose")
+    ///     .expect_pass();
+    /// ```
+    pub fn arg<T>(self, arg: T) -> Self
+    where
+        T: AsRef<str>,
+    {
+        Self(self.0.arg(arg))
+    }
+
+    /// Adds multiple arguments to pass to the cargo command.
+    ///
+    /// Note that `cargo check`, `cargo clippy` and `cargo doc` get run with
+    /// `--message-format=json`, so passing `--message-format` to them panics.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// tryexpand::run(["tests/*.rs"])
+    ///     .args(["--features", "test-feature"])
+    ///     .expect_pass();
+    /// ```
+    pub fn args<T, I>(self, args: I) -> Self
+    where
+        T: AsRef<str>,
+        I: IntoIterator<Item = T>,
+    {
+        Self(self.0.args(args))
+    }
+
+    /// Sets an environment variable for the test execution.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// tryexpand::run(["tests/*.rs"])
+    ///     .env("MY_VAR", "value")
+    ///     .expect_pass();
+    /// ```
+    pub fn env<K, V>(self, key: K, value: V) -> Self
+    where
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        Self(self.0.env(key, value))
+    }
+
+    /// Sets multiple environment variables for the test execution.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// tryexpand::run_tests(["tests/*.rs"])
+    ///     .envs([("VAR1", "value1"), ("VAR2", "value2")])
+    ///     .expect_pass();
+    /// ```
+    pub fn envs<K, V, I>(self, envs: I) -> Self
+    where
+        K: AsRef<str>,
+        V: AsRef<str>,
+        I: IntoIterator<Item = (K, V)>,
+    {
+        Self(self.0.envs(envs))
+    }
+
+    /// Builds test files (and their dependencies) with the `release` profile,
+    /// instead of the `dev` profile.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// tryexpand::check(["tests/*.rs"])
+    ///     .release()
+    ///     .expect_pass();
+    /// ```
+    pub fn release(self) -> Self {
+        Self(self.0.release())
+    }
+
+    /// Sets the `opt-level` (e.g. `"0"`, `"3"`, `"s"` or `"z"`)
+    /// of the profile that test files get built with.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// tryexpand::check(["tests/*.rs"])
+    ///     .opt_level("3")
+    ///     .expect_pass();
+    /// ```
+    pub fn opt_level<T>(self, level: T) -> Self
+    where
+        T: AsRef<str>,
+    {
+        Self(self.0.opt_level(level))
+    }
+
+    /// Enables (or disables) `debug-assertions`
+    /// for the profile that test files get built with.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// tryexpand::check(["tests/*.rs"])
+    ///     .debug_assertions(false)
+    ///     .expect_pass();
+    /// ```
+    pub fn debug_assertions(self, enabled: bool) -> Self {
+        Self(self.0.debug_assertions(enabled))
+    }
+
+    /// Enables (or disables) `overflow-checks`
+    /// for the profile that test files get built with.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// tryexpand::check(["tests/*.rs"])
+    ///     .overflow_checks(false)
+    ///     .expect_pass();
+    /// ```
+    pub fn overflow_checks(self, enabled: bool) -> Self {
+        Self(self.0.overflow_checks(enabled))
+    }
+
+    /// Sets `panic = "abort"` for the profile that test files get built with.
+    ///
+    /// Note that cargo ignores this setting for `cargo test`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// tryexpand::check(["tests/*.rs"])
+    ///     .panic_abort()
+    ///     .expect_pass();
+    /// ```
+    pub fn panic_abort(self) -> Self {
+        Self(self.0.panic_abort())
+    }
+
+    /// Enables the given features of the crate under test
+    /// (for all cargo commands, including the prebuild of dependencies).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// tryexpand::check(["tests/*.rs"])
+    ///     .features(["my-feature"])
+    ///     .expect_pass();
+    /// ```
+    pub fn features<T, I>(self, features: I) -> Self
+    where
+        T: AsRef<str>,
+        I: IntoIterator<Item = T>,
+    {
+        Self(self.0.features(features))
+    }
+
+    /// Enables all features of the crate under test
+    /// (for all cargo commands, including the prebuild of dependencies).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// tryexpand::check(["tests/*.rs"])
+    ///     .all_features()
+    ///     .expect_pass();
+    /// ```
+    pub fn all_features(self) -> Self {
+        Self(self.0.all_features())
+    }
+
+    /// Disables the default features of the crate under test
+    /// (for all cargo commands, including the prebuild of dependencies).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// tryexpand::check(["tests/*.rs"])
+    ///     .no_default_features()
+    ///     .features(["my-feature"])
+    ///     .expect_pass();
+    /// ```
+    pub fn no_default_features(self) -> Self {
+        Self(self.0.no_default_features())
+    }
+
+    /// Enables the given `--cfg` option for test files (and their dependencies),
+    /// e.g. for testing `#[cfg(my_cfg)]`-gated macro branches.
+    ///
+    /// The option also gets declared via `--check-cfg`, so it doesn't trigger
+    /// `unexpected_cfgs` warnings.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// tryexpand::check(["tests/*.rs"])
+    ///     .cfg("my_cfg")
+    ///     .expect_pass();
+    /// ```
+    pub fn cfg<T>(self, name: T) -> Self
+    where
+        T: AsRef<str>,
+    {
+        Self(self.0.cfg(name))
+    }
+
+    /// Enables the given key-value `--cfg` option (i.e. `name="value"`) for test files
+    /// (and their dependencies), e.g. for testing `#[cfg(backend = "x")]`-gated macro branches.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// tryexpand::check(["tests/*.rs"])
+    ///     .cfg_value("backend", "x")
+    ///     .expect_pass();
+    /// ```
+    pub fn cfg_value<N, V>(self, name: N, value: V) -> Self
+    where
+        N: AsRef<str>,
+        V: AsRef<str>,
+    {
+        Self(self.0.cfg_value(name, value))
+    }
+
+    /// Passes additional flags to `rustc` for test files (and their dependencies).
+    ///
+    /// Unlike passing `RUSTFLAGS` via `.env(…)`/`.envs(…)`, these get passed
+    /// consistently to all cargo commands (including the prebuild of dependencies),
+    /// in addition to the flags of the environment and the cargo config.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// tryexpand::check(["tests/*.rs"])
+    ///     .rustflags(["-Ctarget-cpu=native"])
+    ///     .expect_pass();
+    /// ```
+    pub fn rustflags<T, I>(self, rustflags: I) -> Self
+    where
+        T: AsRef<str>,
+        I: IntoIterator<Item = T>,
+    {
+        Self(self.0.rustflags(rustflags))
+    }
+
+    /// Selects the package under test by name (e.g. a member of the current workspace),
+    /// instead of the calling package.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// tryexpand::check(["tests/*.rs"])
+    ///     .package("my-derive")
+    ///     .expect_pass();
+    /// ```
+    pub fn package<T>(self, name: T) -> Self
+    where
+        T: AsRef<str>,
+    {
+        Self(self.0.package(name))
+    }
+
+    /// Selects the package under test by the path of its manifest
+    /// (relative to the current directory, i.e. the calling package's),
+    /// instead of the calling package.
+    ///
+    /// If the manifest belongs to a virtual workspace, then the package
+    /// has to be selected via [`package()`](Self::package) additionally.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// tryexpand::check(["tests/*.rs"])
+    ///     .manifest_path("my-derive/Cargo.toml")
+    ///     .expect_pass();
+    /// ```
+    pub fn manifest_path<P>(self, path: P) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        Self(self.0.manifest_path(path))
+    }
+
+    /// Adds another member of the workspace as a dependency, which test files can then use.
+    ///
+    /// This is useful for testing macros together with their runtime crates, for example.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// tryexpand::check(["tests/*.rs"])
+    ///     .workspace_dependency("my-runtime")
+    ///     .expect_pass();
+    /// ```
+    pub fn workspace_dependency<T>(self, name: T) -> Self
+    where
+        T: AsRef<str>,
+    {
+        Self(self.0.workspace_dependency(name))
+    }
+
+    /// Makes the crate under test available to test files under the given alias,
+    /// as if it had been declared as `alias = { package = "…" }` by its users.
+    ///
+    /// This is useful for catching hard-coded paths (e.g. `::my_crate::…`)
+    /// in macro expansions, which break for users who renamed the dependency.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// tryexpand::check(["tests/*.rs"])
+    ///     .crate_alias("renamed")
+    ///     .expect_pass();
+    /// ```
+    pub fn crate_alias<T>(self, alias: T) -> Self
+    where
+        T: AsRef<str>,
+    {
+        Self(self.0.crate_alias(alias))
+    }
+
+    /// Adds a dependency from crates.io, which test files can then use.
+    ///
+    /// This is useful for test files that depend on helper crates
+    /// which aren't dependencies of the crate under test.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// tryexpand::check(["tests/*.rs"])
+    ///     .dependency("serde_json", "1")
+    ///     .expect_pass();
+    /// ```
+    pub fn dependency<N, V>(self, name: N, version: V) -> Self
+    where
+        N: AsRef<str>,
+        V: AsRef<str>,
+    {
+        Self(self.0.dependency(name, version))
+    }
+
+    /// Adds a dependency from a local path, which test files can then use.
+    ///
+    /// Relative paths are resolved against the crate under test's directory.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// tryexpand::check(["tests/*.rs"])
+    ///     .path_dependency("helper", "tests/helper")
+    ///     .expect_pass();
+    /// ```
+    pub fn path_dependency<N, P>(self, name: N, path: P) -> Self
+    where
+        N: AsRef<str>,
+        P: AsRef<Path>,
+    {
+        Self(self.0.path_dependency(name, path))
+    }
+
+    /// Attaches the given build script (relative to the crate under test)
+    /// to the test project, e.g. for testing macros that `include!` code
+    /// generated into `OUT_DIR`.
+    ///
+    /// The build script has access to the crate under test's build-dependencies.
+    /// Its `cargo:warning=…` output gets snapshotted by `tryexpand::check(…)`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// tryexpand::check(["tests/*.rs"])
+    ///     .build_script("tests/support/build.rs")
+    ///     .expect_pass();
+    /// ```
+    pub fn build_script<P>(self, path: P) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        Self(self.0.build_script(path))
+    }
+
+    /// Copies the given files or directories (relative to the crate under test)
+    /// to the same relative paths within the test project.
+    ///
+    /// This is useful for macros that read files relative to `CARGO_MANIFEST_DIR`,
+    /// which points to the test project, rather than the crate under test.
+    ///
+    /// Fixtures must not replace the test project as a whole (e.g. `"."`),
+    /// nor its generated files (e.g. `Cargo.toml` or `.cargo/config.toml`).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// tryexpand::check(["tests/*.rs"])
+    ///     .fixtures(["tests/data", "config.toml"])
+    ///     .expect_pass();
+    /// ```
+    pub fn fixtures<P, I>(self, paths: I) -> Self
+    where
+        P: AsRef<Path>,
+        I: IntoIterator<Item = P>,
+    {
+        Self(self.0.fixtures(paths))
+    }
+
+    /// Makes the crate under test's dev-dependencies available to test files.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// tryexpand::check(["tests/*.rs"])
+    ///     .with_dev_dependencies()
+    ///     .expect_pass();
+    /// ```
+    pub fn with_dev_dependencies(self) -> Self {
+        Self(self.0.with_dev_dependencies())
+    }
+
+    /// Allows the given lint (e.g. `"clippy::needless_return"`) when running `cargo clippy`.
+    ///
+    /// Only has an effect on [`crate::clippy`] and [`ExpandTestSuite::and_clippy`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// tryexpand::clippy(["tests/*.rs"])
+    ///     .clippy_allow("clippy::needless_return")
+    ///     .expect_pass();
+    /// ```
+    pub fn clippy_allow<T>(self, lint: T) -> Self
+    where
+        T: AsRef<str>,
+    {
+        Self(self.0.clippy_allow(lint))
+    }
+
+    /// Warns about the given lint (e.g. `"clippy::pedantic"`) when running `cargo clippy`.
+    ///
+    /// Only has an effect on [`crate::clippy`] and [`ExpandTestSuite::and_clippy`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// tryexpand::clippy(["tests/*.rs"])
+    ///     .clippy_warn("clippy::pedantic")
+    ///     .expect_pass();
+    /// ```
+    pub fn clippy_warn<T>(self, lint: T) -> Self
+    where
+        T: AsRef<str>,
+    {
+        Self(self.0.clippy_warn(lint))
+    }
+
+    /// Denies the given lint (e.g. `"clippy::unwrap_used"`) when running `cargo clippy`,
+    /// turning any occurrence into an error.
+    ///
+    /// Only has an effect on [`crate::clippy`] and [`ExpandTestSuite::and_clippy`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// tryexpand::clippy(["tests/*.rs"])
+    ///     .clippy_deny("clippy::unwrap_used")
+    ///     .expect_fail();
+    /// ```
+    pub fn clippy_deny<T>(self, lint: T) -> Self
+    where
+        T: AsRef<str>,
+    {
+        Self(self.0.clippy_deny(lint))
+    }
+
+    /// Compiles the test files as library targets (rather than binaries),
+    /// which allows for them to omit `fn main()` and use `#![no_std]`.
+    ///
+    /// The libraries are named after their file's stem (e.g. `tests/foo.rs` becomes `foo`).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// tryexpand::check(["tests/*.rs"])
+    ///     .as_lib()
+    ///     .expect_pass();
+    /// ```
+    #[allow(clippy::wrong_self_convention)]
+    pub fn as_lib(self) -> Self {
+        Self(self.0.as_lib())
+    }
+
+    /// Keeps compiler warnings (which are suppressed via `-Awarnings` by default)
+    /// and includes them in the `.err.txt` snapshots.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// tryexpand::check(["tests/*.rs"])
+    ///     .with_warnings()
+    ///     .expect_pass();
+    /// ```
+    pub fn with_warnings(self) -> Self {
+        Self(self.0.with_warnings())
+    }
+
+    /// Additionally snapshots the compiler's structured diagnostics of `cargo check`
+    /// (i.e. their levels, codes, messages and spans) as `.err.json`,
+    /// which is less sensitive to changes in how rustc renders them.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// tryexpand::check(["tests/*.rs"])
+    ///     .with_json_diagnostics()
+    ///     .expect_fail();
+    /// ```
+    pub fn with_json_diagnostics(self) -> Self {
+        Self(self.0.with_json_diagnostics())
+    }
 
-impl BuildTestSuite {
-    /// Adds a single argument to pass to the cargo command (`check`, `run`, or `test`).
+    /// Skips the `.err.txt` snapshots, e.g. for relying on `//~ ERROR …`
+    /// annotations within test files instead, which are less sensitive
+    /// to changes in how rustc renders its diagnostics.
     ///
     /// # Examples
     ///
     /// ```no_run
     /// tryexpand::check(["tests/*.rs"])
-    ///     .arg("--verbose")
-    ///     .expect_pass();
+    ///     .without_stderr_snapshots()
+    ///     .expect_fail();
     /// ```
-    pub fn arg<T>(self, arg: T) -> Self
-    where
-        T: AsRef<str>,
-    {
-        Self(self.0.arg(arg))
+    pub fn without_stderr_snapshots(self) -> Self {
+        Self(self.0.without_stderr_snapshots())
     }
 
-    /// Adds multiple arguments to pass to the cargo command.
+    /// Like [`with_warnings()`](Self::with_warnings), but additionally
+    /// treats any emitted warnings as test failures.
     ///
     /// # Examples
     ///
     /// ```no_run
-    /// tryexpand::run(["tests/*.rs"])
-    ///     .args(["--features", "test-feature"])
+    /// tryexpand::check(["tests/*.rs"])
+    ///     .deny_warnings()
     ///     .expect_pass();
     /// ```
-    pub fn args<T, I>(self, args: I) -> Self
-    where
-        T: AsRef<str>,
-        I: IntoIterator<Item = T>,
-    {
-        Self(self.0.args(args))
+    pub fn deny_warnings(self) -> Self {
+        Self(self.0.deny_warnings())
     }
 
-    /// Sets an environment variable for the test execution.
+    /// Prevents overwriting existing snapshot files even when `TRYEXPAND=overwrite` is set.
+    ///
+    /// This is useful when you want to preserve specific snapshots while allowing
+    /// others to be updated during test runs.
     ///
     /// # Examples
     ///
     /// ```no_run
-    /// tryexpand::run(["tests/*.rs"])
-    ///     .env("MY_VAR", "value")
+    /// tryexpand::check(["tests/*.rs"])
+    ///     .skip_overwrite()
     ///     .expect_pass();
     /// ```
-    pub fn env<K, V>(self, key: K, value: V) -> Self
-    where
-        K: AsRef<str>,
-        V: AsRef<str>,
-    {
-        Self(self.0.env(key, value))
+    pub fn skip_overwrite(self) -> Self {
+        Self(self.0.skip_overwrite())
     }
 
-    /// Sets multiple environment variables for the test execution.
+    /// Seeds the test project with the source project's `Cargo.lock`
+    /// and passes `--locked` to all cargo commands.
+    ///
+    /// This ensures that the tests use the exact same dependency versions
+    /// as the source project, failing if the lockfile is missing or stale.
     ///
     /// # Examples
     ///
     /// ```no_run
-    /// tryexpand::run_tests(["tests/*.rs"])
-    ///     .envs([("VAR1", "value1"), ("VAR2", "value2")])
+    /// tryexpand::check(["tests/*.rs"])
+    ///     .locked()
     ///     .expect_pass();
     /// ```
-    pub fn envs<K, V, I>(self, envs: I) -> Self
-    where
-        K: AsRef<str>,
-        V: AsRef<str>,
-        I: IntoIterator<Item = (K, V)>,
-    {
-        Self(self.0.envs(envs))
+    pub fn locked(self) -> Self {
+        Self(self.0.locked())
     }
 
-    /// Prevents overwriting existing snapshot files even when `TRYEXPAND=overwrite` is set.
+    /// Passes `--offline` to all cargo commands.
     ///
-    /// This is useful when you want to preserve specific snapshots while allowing
-    /// others to be updated during test runs.
+    /// This prevents cargo from accessing the network,
+    /// requiring all dependencies to be available locally.
     ///
     /// # Examples
     ///
     /// ```no_run
     /// tryexpand::check(["tests/*.rs"])
-    ///     .skip_overwrite()
+    ///     .offline()
     ///     .expect_pass();
     /// ```
-    pub fn skip_overwrite(self) -> Self {
-        Self(self.0.skip_overwrite())
+    pub fn offline(self) -> Self {
+        Self(self.0.offline())
     }
 
     /// Applies a regex filter to normalize stdout output before snapshotting.
@@ -487,7 +1513,7 @@ impl BuildTestSuite {
 
 #[derive(Debug)]
 pub(crate) struct TestSuite {
-    pub(crate) project: Project,
+    pub(crate) test_suite_id: String,
     pub(crate) plan: TestPlan,
     pub(crate) tests: Vec<Test>,
     pub(crate) options: Options,
@@ -519,9 +1545,7 @@ impl TestSuite {
                         pattern.as_ref().to_owned(),
                         paths
                             .into_iter()
-                            .filter(|path| {
-                                !path.to_string_lossy().ends_with(crate::OUT_RS_FILE_SUFFIX)
-                            })
+                            .filter(|path| !is_snapshot_path(path))
                             .collect(),
                     )
                 })
@@ -556,37 +1580,14 @@ impl TestSuite {
 
         let paths: Vec<PathBuf> = Vec::from_iter(unique_paths);
 
-        let metadata = cargo_metadata::MetadataCommand::new()
-            .exec()
-            .map_err(|source| Error::CargoMetadata {
-                directory: std::env::current_dir().unwrap(),
-                source,
-            })?;
-
-        let crate_name = env::var("CARGO_PKG_NAME")
-            .ok()
-            .or_else(|| metadata.root_package().map(|pkg| pkg.name.to_string()))
-            .ok_or(Error::CargoPkgName)?;
-
-        let package = metadata
-            .packages
-            .iter()
-            .find(|package| package.name.as_str() == crate_name)
-            .ok_or(Error::CargoPackageNotFound)?;
-
-        let target_dir = env::var("CARGO_TARGET_DIR")
-            .map(PathBuf::from)
-            .unwrap_or_else(|_| metadata.target_directory.as_std_path().to_owned());
+        // The package under test gets resolved lazily (as it can be selected explicitly),
+        // so the crate's name merely serves as a human-readable prefix of the tests' binaries:
+        let crate_name = env::var("CARGO_PKG_NAME").unwrap_or_else(|_| "tryexpand".to_owned());
 
-        let tests = Self::tests_for(&crate_name, paths);
+        let tests = Self::tests_for(&crate_name, paths)?;
 
         let test_suite_id = test_suite_id_from_location(location);
 
-        let project = Project::new(&metadata, package, &test_suite_id, &target_dir, &tests)
-            .unwrap_or_else(|err| {
-                panic!("Could not create test project: {:#?}", err);
-            });
-
         let plan = TestPlan {
             action,
             post_action: None,
@@ -594,7 +1595,17 @@ impl TestSuite {
             expectation: TestStatus::Success,
         };
 
-        let options = Options::default();
+        // Doctests are only supported for library targets,
+        // whose public API is what documentation is usually generated for:
+        let target_kind = match action {
+            Action::Doc | Action::DocTest => TargetKind::Lib,
+            _ => TargetKind::Bin,
+        };
+
+        let options = Options {
+            target_kind,
+            ..Default::default()
+        };
 
         let call_site = format!(
             "{file}:{line}:{column}",
@@ -604,7 +1615,7 @@ impl TestSuite {
         );
 
         Ok(Self {
-            project,
+            test_suite_id,
             plan,
             tests,
             options,
@@ -652,11 +1663,252 @@ impl TestSuite {
         self
     }
 
+    pub(crate) fn release(mut self) -> Self {
+        self.options.release = true;
+        self
+    }
+
+    pub(crate) fn opt_level<T>(mut self, level: T) -> Self
+    where
+        T: AsRef<str>,
+    {
+        let level = level.as_ref();
+        // Cargo expects numeric levels as integers, rather than strings:
+        let level = match level.parse::<i64>() {
+            Ok(level) => Value::Integer(level),
+            Err(_) => Value::String(level.to_owned()),
+        };
+        self.options.profile.opt_level = Some(level);
+        self
+    }
+
+    pub(crate) fn debug_assertions(mut self, enabled: bool) -> Self {
+        self.options.profile.debug_assertions = Some(enabled);
+        self
+    }
+
+    pub(crate) fn overflow_checks(mut self, enabled: bool) -> Self {
+        self.options.profile.overflow_checks = Some(enabled);
+        self
+    }
+
+    pub(crate) fn panic_abort(mut self) -> Self {
+        self.options.profile.panic = Some("abort".to_owned());
+        self
+    }
+
+    pub(crate) fn features<T, I>(mut self, features: I) -> Self
+    where
+        T: AsRef<str>,
+        I: IntoIterator<Item = T>,
+    {
+        self.options
+            .features
+            .extend(features.into_iter().map(|str| str.as_ref().to_owned()));
+        self
+    }
+
+    pub(crate) fn all_features(mut self) -> Self {
+        self.options.all_features = true;
+        self
+    }
+
+    pub(crate) fn no_default_features(mut self) -> Self {
+        self.options.no_default_features = true;
+        self
+    }
+
+    pub(crate) fn cfg<T>(mut self, name: T) -> Self
+    where
+        T: AsRef<str>,
+    {
+        self.options.cfgs.push((name.as_ref().to_owned(), None));
+        self
+    }
+
+    pub(crate) fn cfg_value<N, V>(mut self, name: N, value: V) -> Self
+    where
+        N: AsRef<str>,
+        V: AsRef<str>,
+    {
+        let name = name.as_ref().to_owned();
+        let value = value.as_ref().to_owned();
+        self.options.cfgs.push((name, Some(value)));
+        self
+    }
+
+    pub(crate) fn rustflags<T, I>(mut self, rustflags: I) -> Self
+    where
+        T: AsRef<str>,
+        I: IntoIterator<Item = T>,
+    {
+        self.options
+            .rustflags
+            .extend(rustflags.into_iter().map(|str| str.as_ref().to_owned()));
+        self
+    }
+
+    pub(crate) fn package<T>(mut self, name: T) -> Self
+    where
+        T: AsRef<str>,
+    {
+        self.options.package = Some(name.as_ref().to_owned());
+        self
+    }
+
+    pub(crate) fn manifest_path<P>(mut self, path: P) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        let path = env::current_dir().unwrap().join(path);
+        self.options.manifest_path = Some(path);
+        self
+    }
+
+    pub(crate) fn workspace_dependency<T>(mut self, name: T) -> Self
+    where
+        T: AsRef<str>,
+    {
+        let name = name.as_ref().to_owned();
+        self.options.workspace_dependencies.push(name);
+        self
+    }
+
+    pub(crate) fn crate_alias<T>(mut self, alias: T) -> Self
+    where
+        T: AsRef<str>,
+    {
+        self.options.crate_alias = Some(alias.as_ref().to_owned());
+        self
+    }
+
+    pub(crate) fn dependency<N, V>(mut self, name: N, version: V) -> Self
+    where
+        N: AsRef<str>,
+        V: AsRef<str>,
+    {
+        let name = name.as_ref().to_owned();
+        // Detailed (rather than simple) so that the generated manifest
+        // doesn't end up mixing plain values and tables within `[dependencies]`:
+        let dependency = Dependency::Detailed(Box::new(DependencyDetail {
+            version: Some(version.as_ref().to_owned()),
+            ..Default::default()
+        }));
+        self.options.dependencies.insert(name, dependency);
+        self
+    }
+
+    pub(crate) fn path_dependency<N, P>(mut self, name: N, path: P) -> Self
+    where
+        N: AsRef<str>,
+        P: AsRef<Path>,
+    {
+        let name = name.as_ref().to_owned();
+        // Gets resolved against the crate under test's directory, once that's known:
+        let path = path.as_ref();
+        let dependency = Dependency::Detailed(Box::new(DependencyDetail {
+            path: Some(path.display().to_string()),
+            ..Default::default()
+        }));
+        self.options.dependencies.insert(name, dependency);
+        self
+    }
+
+    pub(crate) fn build_script<P>(mut self, path: P) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        self.options.build_script = Some(path.as_ref().to_owned());
+        self
+    }
+
+    pub(crate) fn fixtures<P, I>(mut self, paths: I) -> Self
+    where
+        P: AsRef<Path>,
+        I: IntoIterator<Item = P>,
+    {
+        self.options
+            .fixtures
+            .extend(paths.into_iter().map(|path| path.as_ref().to_owned()));
+        self
+    }
+
+    pub(crate) fn with_dev_dependencies(mut self) -> Self {
+        self.options.dev_dependencies = true;
+        self
+    }
+
+    pub(crate) fn clippy_allow<T>(self, lint: T) -> Self
+    where
+        T: AsRef<str>,
+    {
+        self.clippy_lint("-A", lint)
+    }
+
+    pub(crate) fn clippy_warn<T>(self, lint: T) -> Self
+    where
+        T: AsRef<str>,
+    {
+        self.clippy_lint("-W", lint)
+    }
+
+    pub(crate) fn clippy_deny<T>(self, lint: T) -> Self
+    where
+        T: AsRef<str>,
+    {
+        self.clippy_lint("-D", lint)
+    }
+
+    fn clippy_lint<T>(mut self, flag: &str, lint: T) -> Self
+    where
+        T: AsRef<str>,
+    {
+        let lint = lint.as_ref();
+        self.options.clippy_lints.push(format!("{flag}{lint}"));
+        self
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    pub(crate) fn as_lib(mut self) -> Self {
+        self.options.target_kind = TargetKind::Lib;
+        self
+    }
+
+    pub(crate) fn with_warnings(mut self) -> Self {
+        self.options.warnings = Warnings::Report;
+        self
+    }
+
+    pub(crate) fn with_json_diagnostics(mut self) -> Self {
+        self.options.json_diagnostics = true;
+        self
+    }
+
+    pub(crate) fn without_stderr_snapshots(mut self) -> Self {
+        self.options.skip_stderr_snapshots = true;
+        self
+    }
+
+    pub(crate) fn deny_warnings(mut self) -> Self {
+        self.options.warnings = Warnings::Deny;
+        self
+    }
+
     pub(crate) fn skip_overwrite(mut self) -> Self {
         self.options.skip_overwrite = true;
         self
     }
 
+    pub(crate) fn locked(mut self) -> Self {
+        self.options.locked = true;
+        self
+    }
+
+    pub(crate) fn offline(mut self) -> Self {
+        self.options.offline = true;
+        self
+    }
+
     pub(crate) fn filter_stdout<P, R>(self, pattern: P, replacement: R) -> Self
     where
         P: AsRef<str>,
@@ -701,6 +1953,14 @@ impl TestSuite {
         self.and_post_check(Action::Check)
     }
 
+    pub(crate) fn and_clippy(self) -> Self {
+        self.and_post_check(Action::Clippy)
+    }
+
+    pub(crate) fn and_doc(self) -> Self {
+        self.and_post_check(Action::Doc)
+    }
+
     pub(crate) fn and_run(self) -> Self {
         self.and_post_check(Action::Run)
     }
@@ -726,6 +1986,9 @@ impl TestSuite {
             let cmd = match existing_action {
                 Action::Expand => panic!("unexpected `expand` as post-action"),
                 Action::Check => "check",
+                Action::Clippy => "clippy",
+                Action::Doc => "doc",
+                Action::DocTest => "test --doc",
                 Action::Test => "test",
                 Action::Run => "run",
             };
@@ -744,13 +2007,68 @@ impl TestSuite {
     #[track_caller] // LOAD-BEARING, DO NOT REMOVE!
     pub(crate) fn try_run(&mut self) -> Result<()> {
         let TestSuite {
-            project,
+            test_suite_id,
             plan,
             tests,
             options,
             call_site,
         } = self;
 
+        if options.target_kind == TargetKind::Lib {
+            let actions = std::iter::once(plan.action).chain(plan.post_action);
+            if actions.into_iter().any(|action| action == Action::Run) {
+                panic!("`cargo run` is not supported for test files compiled as library targets");
+            }
+        }
+
+        // Annotations get verified against the diagnostics of `cargo check`:
+        let verifies_annotations = std::iter::once(plan.action)
+            .chain(plan.post_action)
+            .any(|action| action == Action::Check);
+        for test in tests.iter_mut() {
+            test.load_annotations(verifies_annotations)?;
+        }
+
+        // Diagnostics of these actions get parsed from cargo's JSON messages,
+        // so we can't let the user pick a different message format:
+        let actions = std::iter::once(plan.action).chain(plan.post_action);
+        let emits_json = actions.into_iter().any(|action| {
+            matches!(
+                action,
+                Action::Expand | Action::Check | Action::Clippy | Action::Doc
+            )
+        });
+        if emits_json
+            && options
+                .args
+                .iter()
+                .any(|arg| arg.starts_with("--message-format"))
+        {
+            panic!("`--message-format` is not supported for `cargo expand`, `cargo check`, `cargo clippy` or `cargo doc`, whose diagnostics get parsed as JSON");
+        }
+
+        let (metadata, package) = resolve_package(options).unwrap_or_else(|err| {
+            panic!("Could not resolve package under test: {:#?}", err);
+        });
+
+        let target_dir = env::var("CARGO_TARGET_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| metadata.target_directory.as_std_path().to_owned());
+
+        // The project gets created lazily, so that it can take
+        // all of the test suite's configured options into account:
+        let project = Project::new(
+            &metadata,
+            &package,
+            test_suite_id,
+            &target_dir,
+            tests.iter(),
+            options,
+        )
+        .unwrap_or_else(|err| {
+            panic!("Could not create test project: {:#?}", err);
+        });
+
         let total_tests = tests.len();
 
         println!(
@@ -760,15 +2078,27 @@ impl TestSuite {
         );
 
         let mut failures = HashSet::new();
+        // Annotation mismatches get summarized along with their tests' paths:
+        let mut mismatches: HashMap<PathBuf, Vec<String>> = HashMap::new();
 
         let max_errors = 2;
         let mut command_errors = 0;
 
         for test in tests {
             let test_path = test.path.to_owned();
-            let result = test.run(plan, project, options, &mut |outcome| {
+            let result = test.run(plan, &project, options, &mut |outcome| {
                 message::report_outcome(&test_path, &outcome);
 
+                if let TestOutcome::AnnotationMismatch(mismatch) = &outcome {
+                    let lines = mismatches.entry(test_path.clone()).or_default();
+                    for annotation in &mismatch.unmatched {
+                        lines.push(format!("not found: {annotation}"));
+                    }
+                    for diagnostic in &mismatch.unexpected {
+                        lines.push(format!("unexpected: {diagnostic}"));
+                    }
+                }
+
                 match outcome.as_status() {
                     TestStatus::Success => {}
                     TestStatus::Failure => {
@@ -806,6 +2136,9 @@ impl TestSuite {
 
             for failure in sorted_failures {
                 writeln!(&mut message, "    {}", failure.display()).unwrap();
+                for line in mismatches.get(&failure).into_iter().flatten() {
+                    writeln!(&mut message, "        {line}").unwrap();
+                }
             }
 
             eprintln!();
@@ -815,7 +2148,7 @@ impl TestSuite {
         Ok(())
     }
 
-    fn tests_for<I, P>(crate_name: &str, paths: I) -> Vec<Test>
+    fn tests_for<I, P>(crate_name: &str, paths: I) -> Result<Vec<Test>>
     where
         I: IntoIterator<Item = P>,
         P: AsRef<Path>,
@@ -833,13 +2166,13 @@ impl TestSuite {
                     let test_id = base62::encode(hasher.finish()).to_lowercase();
                     format!("{crate_name}_{test_id}")
                 };
-                Test { bin, path }
+                Test::new(bin, path)
             })
-            .collect();
+            .collect::<Result<_>>()?;
 
         tests.sort_by_cached_key(|test| test.path.clone());
 
-        tests
+        Ok(tests)
     }
 }
 
@@ -882,6 +2215,54 @@ pub(crate) fn test_behavior_from_env() -> Result<TestBehavior> {
     }
 }
 
+fn resolve_package(options: &Options) -> Result<(Metadata, Package)> {
+    let mut command = cargo_metadata::MetadataCommand::new();
+
+    if let Some(manifest_path) = &options.manifest_path {
+        command.manifest_path(manifest_path);
+    }
+
+    let metadata = command.exec().map_err(|source| Error::CargoMetadata {
+        directory: std::env::current_dir().unwrap(),
+        source,
+    })?;
+
+    let crate_name = match (&options.package, &options.manifest_path) {
+        (Some(package), _) => Some(package.clone()),
+        // An explicitly provided manifest takes precedence over the calling package:
+        (None, Some(_)) => metadata.root_package().map(|pkg| pkg.name.to_string()),
+        (None, None) => env::var("CARGO_PKG_NAME")
+            .ok()
+            .or_else(|| metadata.root_package().map(|pkg| pkg.name.to_string())),
+    }
+    .ok_or(Error::CargoPkgName)?;
+
+    // Only packages of the workspace itself can be tested, not their dependencies:
+    let package = metadata
+        .workspace_packages()
+        .into_iter()
+        .find(|package| package.name.as_str() == crate_name)
+        .cloned()
+        .ok_or(Error::CargoPackageNotFound { name: crate_name })?;
+
+    Ok((metadata, package))
+}
+
+// Patterns (e.g. `tests/expand/*.rs` or `tests/expand/*`) may also match
+// the test cases' snapshots, which we need to skip:
+fn is_snapshot_path(path: &Path) -> bool {
+    let path = path.to_string_lossy();
+
+    [
+        crate::OUT_RS_FILE_SUFFIX,
+        crate::OUT_TXT_FILE_SUFFIX,
+        crate::ERR_TXT_FILE_SUFFIX,
+        crate::ERR_JSON_FILE_SUFFIX,
+    ]
+    .iter()
+    .any(|suffix| path.ends_with(suffix))
+}
+
 fn expand_globs(path: impl AsRef<Path>) -> Result<Vec<PathBuf>> {
     let path = path.as_ref();
 
@@ -1,4 +1,4 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use crate::error::{Error, Result};
 
@@ -24,6 +24,32 @@ where
     })
 }
 
+pub(crate) fn read_dir<P>(path: P) -> Result<Vec<PathBuf>>
+where
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let map_err = |source| Error::ReadingDirectoryFailed {
+        path: path.to_owned(),
+        source,
+    };
+    std::fs::read_dir(path)
+        .map_err(map_err)?
+        .map(|entry| entry.map(|entry| entry.path()).map_err(map_err))
+        .collect()
+}
+
+pub(crate) fn canonicalize<P>(path: P) -> Result<PathBuf>
+where
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    std::fs::canonicalize(path).map_err(|source| Error::ResolvingPathFailed {
+        path: path.to_owned(),
+        source,
+    })
+}
+
 pub(crate) fn read<P>(path: P) -> Result<Vec<u8>>
 where
     P: AsRef<Path>,
@@ -47,6 +73,47 @@ where
     })
 }
 
+pub(crate) fn copy<P, Q>(from: P, to: Q) -> Result<()>
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+{
+    let (from, to) = (from.as_ref(), to.as_ref());
+    std::fs::copy(from, to)
+        .map(|_| ())
+        .map_err(|source| Error::CopyingFileFailed {
+            from: from.to_owned(),
+            to: to.to_owned(),
+            source,
+        })
+}
+
+/// Copies the file or directory (including its contents) at `from` to `to`,
+/// creating any missing parent directories of `to`.
+pub(crate) fn copy_all<P, Q>(from: P, to: Q) -> Result<()>
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+{
+    let (from, to) = (from.as_ref(), to.as_ref());
+
+    if from.is_dir() {
+        create_dir_all(to)?;
+        for entry in read_dir(from)? {
+            let file_name = entry
+                .file_name()
+                .expect("directory entry should have a name");
+            copy_all(&entry, to.join(file_name))?;
+        }
+        Ok(())
+    } else {
+        if let Some(parent) = to.parent() {
+            create_dir_all(parent)?;
+        }
+        copy(from, to)
+    }
+}
+
 pub(crate) fn should_debug_log() -> Result<bool> {
     let key = crate::TRYEXPAND_DEBUG_LOG_ENV_KEY;
     let Some(var) = std::env::var_os(key) else {
@@ -0,0 +1,6 @@
+pub fn revision() -> &'static str {
+    "v0.1.0"
+}
+
+#[cfg(feature = "default-feature")]
+pub fn default_feature() {}
@@ -0,0 +1,26 @@
+#!/usr/bin/env sh
+# Creates the git repository at `/tmp/tryexpand-git-fixture` (from `./fixture`)
+# that the `git-dependency-tests` crate depends on.
+set -eu
+
+FIXTURE_DIR="$(cd "$(dirname "$0")" && pwd)/fixture"
+REPO_DIR=/tmp/tryexpand-git-fixture
+
+rm -rf "$REPO_DIR"
+cp -R "$FIXTURE_DIR" "$REPO_DIR"
+cd "$REPO_DIR"
+# Fixed identities and dates keep the commit hashes (which get pinned via `rev`) stable:
+export GIT_AUTHOR_NAME=tryexpand GIT_AUTHOR_EMAIL=tryexpand@localhost
+export GIT_COMMITTER_NAME=tryexpand GIT_COMMITTER_EMAIL=tryexpand@localhost
+export GIT_AUTHOR_DATE="2024-01-01T00:00:00Z" GIT_COMMITTER_DATE="2024-01-01T00:00:00Z"
+
+git init --quiet --initial-branch=main
+git add --all
+git commit --quiet --message "v0.1.0"
+git tag v0.1.0
+git checkout --quiet -b feature/#1
+sed -i.bak 's/"v0.1.0"/"feature\/#1"/' src/lib.rs && rm src/lib.rs.bak
+git commit --quiet --all --message "Feature"
+git checkout --quiet main
+echo 'pub fn unreleased() {}' >> src/lib.rs
+git commit --quiet --all --message "Unreleased"
@@ -0,0 +1 @@
+pub use renamed_fixture::revision;
@@ -0,0 +1,3 @@
+fn main() {
+    renamed_fixture::default_feature();
+}
@@ -0,0 +1,4 @@
+fn main() {
+    rev_fixture::unreleased();
+    println!("{}", rev_fixture::revision());
+}
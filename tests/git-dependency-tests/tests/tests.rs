@@ -0,0 +1,19 @@
+mod git {
+    const PASS_PATTERN: &str = "tests/git/pass/*.rs";
+
+    #[test]
+    pub fn pass() {
+        tryexpand::run([PASS_PATTERN]);
+    }
+}
+
+mod default_features {
+    const FAIL_PATTERN: &str = "tests/default_features/fail/*.rs";
+
+    #[test]
+    pub fn fail() {
+        tryexpand::check([FAIL_PATTERN])
+            .filter_stderr(r"--> \S+/checkouts/[^/]+/[^/]+/", "--> <CHECKOUT>/")
+            .expect_fail();
+    }
+}
@@ -0,0 +1,6 @@
+fn main() {
+    let _value: u8 = "forty-two"; //~ ERROR E0308
+
+    let _other: bool = 42;
+    //~^ ERROR mismatched types
+}
@@ -0,0 +1,5 @@
+fn main() {
+    let _value: u8 = "forty-two";
+
+    //~^^^ ERROR E0308
+}
@@ -0,0 +1,5 @@
+mod module;
+
+fn main() {
+    module::run();
+}
@@ -0,0 +1,3 @@
+pub fn run() {
+    let _value: u8 = "forty-two"; //~ ERROR E0308
+}
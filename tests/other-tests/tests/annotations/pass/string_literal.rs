@@ -0,0 +1,13 @@
+// Only comments starting with `//~` are annotations, unlike mentions of it (like this one).
+
+fn main() {
+    let _value = "//~ ERROR E0308";
+    let _raw = r#"
+        //~^ ERROR mismatched types
+    "#;
+    let _char = '"'; /* //~ ERROR E0308 */
+
+    let unused = 42;
+    //~^ WARN unused variable: `unused`
+    //~| HELP prefix it with an underscore
+}
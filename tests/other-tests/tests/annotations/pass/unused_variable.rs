@@ -0,0 +1,5 @@
+fn main() {
+    let value = 42;
+    //~^ WARN unused variable: `value`
+    //~| HELP prefix it with an underscore
+}
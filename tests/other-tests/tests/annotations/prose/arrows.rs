@@ -0,0 +1,5 @@
+// Mentions of `//~` (like this one) only get parsed by suites running `cargo check`.
+
+fn main() {
+    println!("//~> not an annotation either");
+}
@@ -0,0 +1,8 @@
+macro_rules! answer {
+    () => {
+        42
+    };
+}
+pub fn answer() -> u32 {
+    42
+}
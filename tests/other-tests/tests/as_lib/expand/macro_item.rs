@@ -0,0 +1,9 @@
+macro_rules! answer {
+    () => {
+        42
+    };
+}
+
+pub fn answer() -> u32 {
+    answer!()
+}
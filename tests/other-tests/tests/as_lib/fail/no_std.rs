@@ -0,0 +1,5 @@
+#![no_std]
+
+pub fn answer() -> String {
+    String::from("42")
+}
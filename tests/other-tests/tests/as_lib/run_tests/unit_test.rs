@@ -0,0 +1,8 @@
+pub fn answer() -> u32 {
+    42
+}
+
+#[test]
+fn answer_is_correct() {
+    assert_eq!(answer(), 42);
+}
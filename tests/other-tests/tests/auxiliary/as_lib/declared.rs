@@ -0,0 +1,5 @@
+// tryexpand: aux = "../support/defines_macro.rs"
+
+pub fn answer() -> &'static str {
+    defines_macro::answer!()
+}
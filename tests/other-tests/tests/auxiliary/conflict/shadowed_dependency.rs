@@ -0,0 +1,5 @@
+// tryexpand: aux = "../support/uuid.rs"
+
+fn main() {
+    let _: u32 = uuid::VERSION;
+}
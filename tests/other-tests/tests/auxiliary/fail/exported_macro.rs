@@ -0,0 +1,5 @@
+// tryexpand: aux = "../support/defines_macro.rs"
+
+fn main() {
+    let _: &str = defines_macro::answer!();
+}
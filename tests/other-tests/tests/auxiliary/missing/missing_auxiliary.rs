@@ -0,0 +1,3 @@
+// tryexpand: aux = "../support/missing.rs"
+
+fn main() {}
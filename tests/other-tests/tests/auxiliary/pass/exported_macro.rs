@@ -0,0 +1,5 @@
+// tryexpand: aux = "../support/defines_macro.rs"
+
+fn main() {
+    let _: u32 = defines_macro::answer!();
+}
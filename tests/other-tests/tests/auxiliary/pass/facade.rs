@@ -0,0 +1,5 @@
+// tryexpand: aux = "../support/facade.rs"
+
+fn main() {
+    let _ = facade::ReExported;
+}
@@ -0,0 +1,9 @@
+#[doc(hidden)]
+pub const ANSWER: u32 = 42;
+
+#[macro_export]
+macro_rules! answer {
+    () => {
+        $crate::ANSWER
+    };
+}
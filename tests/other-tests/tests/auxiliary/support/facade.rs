@@ -0,0 +1 @@
+pub use other_tests::TypeThatRequiresNoFeature as ReExported;
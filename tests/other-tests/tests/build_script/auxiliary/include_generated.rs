@@ -0,0 +1,8 @@
+// tryexpand: aux = "../support/unbuilt.rs"
+
+include!(concat!(env!("OUT_DIR"), "/generated.rs"));
+
+fn main() {
+    assert!(!unbuilt::HAS_OUT_DIR);
+    println!("{GENERATED}");
+}
@@ -0,0 +1,9 @@
+use std::{env, fs, path::Path};
+
+fn main() {
+    let out_dir = env::var_os("OUT_DIR").unwrap();
+    let path = Path::new(&out_dir).join("generated.rs");
+    fs::write(path, "pub const GENERATED: u32 = 42;\n").unwrap();
+
+    println!("cargo::warning=generated `generated.rs`");
+}
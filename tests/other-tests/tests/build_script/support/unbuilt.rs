@@ -0,0 +1,4 @@
+// Auxiliary crates don't get the test files' build script:
+pub const HAS_OUT_DIR: bool = option_env!("OUT_DIR").is_some();
+
+const _: () = assert!(!HAS_OUT_DIR);
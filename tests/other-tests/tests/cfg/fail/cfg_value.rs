@@ -0,0 +1,4 @@
+#[cfg(backend = "x")]
+compile_error!("unexpected `--cfg backend=\"x\"`");
+
+fn main() {}
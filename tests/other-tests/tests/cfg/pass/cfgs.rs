@@ -0,0 +1,13 @@
+#[cfg(not(my_cfg))]
+compile_error!("expected `--cfg my_cfg`");
+
+#[cfg(not(backend = "x"))]
+compile_error!("expected `--cfg backend=\"x\"`");
+
+#[cfg(not(from_rustflags))]
+compile_error!("expected `--cfg from_rustflags`");
+
+#[cfg(not(from_env))]
+compile_error!("expected `--cfg from_env`");
+
+fn main() {}
@@ -0,0 +1,4 @@
+fn main() {
+    let value: i32 = 42;
+    let _ = value as u64;
+}
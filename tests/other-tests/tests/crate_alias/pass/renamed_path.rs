@@ -0,0 +1,4 @@
+fn main() {
+    let _ = renamed::TypeThatRequiresNoFeature;
+    let _ = renamed::TypeThatRequiresTestFeature;
+}
@@ -0,0 +1,3 @@
+fn main() {
+    let _ = serde_json::json!({ "key": "value" });
+}
@@ -0,0 +1,3 @@
+fn main() {
+    let _: either::Either<u8, u8> = either::Either::Left(42);
+}
@@ -0,0 +1,3 @@
+pub fn answer() -> &'static str {
+    "42"
+}
@@ -0,0 +1,5 @@
+mod inner;
+
+fn main() {
+    let _: u32 = inner::answer();
+}
@@ -0,0 +1,5 @@
+mod nested;
+
+pub fn answer() -> u32 {
+    nested::ANSWER
+}
@@ -0,0 +1,6 @@
+#[path = "support.rs"]
+mod renamed;
+
+fn main() {
+    let _ = renamed::answer();
+}
@@ -0,0 +1,2 @@
+/// A documented struct, see https://example.com.
+pub struct Documented;
@@ -0,0 +1,2 @@
+/// A documented struct, see [`Missing`].
+pub struct Documented;
@@ -0,0 +1,9 @@
+/// A documented struct, see [`Documented::new`].
+pub struct Documented;
+
+impl Documented {
+    /// Creates a new [`Documented`].
+    pub fn new() -> Self {
+        Self
+    }
+}
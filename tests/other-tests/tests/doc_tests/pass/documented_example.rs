@@ -0,0 +1,8 @@
+/// Adds one to the given value.
+///
+/// ```
+/// assert_eq!(documented_example::add_one(41), 42);
+/// ```
+pub fn add_one(value: i32) -> i32 {
+    value + 1
+}
@@ -0,0 +1,3 @@
+fn main() {
+    let _: fnv::FnvHasher = fnv::FnvHasher::default();
+}
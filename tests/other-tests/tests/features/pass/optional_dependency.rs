@@ -0,0 +1,3 @@
+fn main() {
+    let _: fnv::FnvHashMap<u8, u8> = fnv::FnvHashMap::default();
+}
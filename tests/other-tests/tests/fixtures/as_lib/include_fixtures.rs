@@ -0,0 +1,6 @@
+#![no_std]
+
+pub const GREETING: &str = include_str!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/fixtures/data/greeting.txt"
+));
@@ -0,0 +1,13 @@
+const GREETING: &str = include_str!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/fixtures/data/greeting.txt"
+));
+
+const CONFIG: &str = include_str!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/fixtures/config.toml"
+));
+
+fn main() {
+    print!("{GREETING}{CONFIG}");
+}
@@ -0,0 +1,3 @@
+pub fn main() {
+    non_existent_macro!();
+}
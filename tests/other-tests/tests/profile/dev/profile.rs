@@ -0,0 +1,15 @@
+use std::{hint::black_box, panic};
+
+fn main() {
+    println!("debug-assertions: {}", cfg!(debug_assertions));
+    println!("panic-abort: {}", cfg!(panic = "abort"));
+
+    // Overflows can only be observed without aborting on panics:
+    if cfg!(panic = "unwind") {
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(|_| {}));
+        let overflowed = panic::catch_unwind(|| black_box(u8::MAX) + 1).is_err();
+        panic::set_hook(hook);
+        println!("overflow-checks: {overflowed}");
+    }
+}
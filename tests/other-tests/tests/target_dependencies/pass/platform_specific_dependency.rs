@@ -0,0 +1,4 @@
+fn main() {
+    let mut buffer = itoa::Buffer::new();
+    let _ = buffer.format(42);
+}
@@ -104,6 +104,16 @@ mod check {
         // to avoid overwriting snapshots of `pass()`:
         tryexpand::check([PASS_PATTERN, UNMATCHED_PATTERN]).skip_overwrite();
     }
+
+    #[test]
+    #[should_panic(expected = "`--message-format` is not supported")]
+    pub fn message_format() {
+        // We need to test with .skip_overwrite()
+        // to avoid overwriting snapshots of `pass()`:
+        tryexpand::check([PASS_PATTERN])
+            .arg("--message-format=short")
+            .skip_overwrite();
+    }
 }
 
 mod run {
@@ -383,3 +393,524 @@ mod envs {
             .expect_fail();
     }
 }
+
+mod locked {
+    const PASS_PATTERN: &str = "tests/locked/pass/*.rs";
+
+    #[test]
+    pub fn pass() {
+        tryexpand::check([PASS_PATTERN]).locked().offline();
+    }
+}
+
+mod target_dependencies {
+    const PASS_PATTERN: &str = "tests/target_dependencies/pass/*.rs";
+
+    #[test]
+    pub fn pass() {
+        tryexpand::check([PASS_PATTERN]);
+    }
+}
+
+mod features {
+    const PASS_PATTERN: &str = "tests/features/pass/*.rs";
+    const FAIL_PATTERN: &str = "tests/features/fail/*.rs";
+
+    #[test]
+    pub fn pass_dependency_feature() {
+        tryexpand::check([PASS_PATTERN]).args(["--features", "fnv-std"]);
+    }
+
+    #[test]
+    pub fn pass_implicit_and_weak_dependency_feature() {
+        tryexpand::check([PASS_PATTERN]).args(["--features", "fnv-implicit,fnv-weak-std"]);
+    }
+
+    #[test]
+    pub fn pass_features() {
+        tryexpand::check([PASS_PATTERN])
+            .no_default_features()
+            .features(["fnv-std"])
+            .expect_pass();
+    }
+
+    #[test]
+    pub fn pass_all_features() {
+        tryexpand::check([PASS_PATTERN])
+            .all_features()
+            .expect_pass();
+    }
+
+    #[test]
+    pub fn fail_weak_dependency_feature() {
+        tryexpand::check([FAIL_PATTERN])
+            .args(["--features", "fnv-weak-std"])
+            .expect_fail();
+    }
+}
+
+mod dependencies {
+    const PASS_PATTERN: &str = "tests/dependencies/pass/*.rs";
+    const FAIL_PATTERN: &str = "tests/dependencies/fail/*.rs";
+
+    #[test]
+    pub fn pass() {
+        tryexpand::check([PASS_PATTERN])
+            .dependency("serde_json", "1")
+            .path_dependency("helper", "tests/dependencies/helper")
+            .with_dev_dependencies();
+    }
+
+    #[test]
+    pub fn fail() {
+        tryexpand::check([FAIL_PATTERN]).expect_fail();
+    }
+
+    #[test]
+    #[should_panic(expected = "DependencyConflict")]
+    pub fn fail_conflicting_dependency() {
+        tryexpand::check([PASS_PATTERN])
+            .dependency("uuid", "1")
+            .skip_overwrite();
+    }
+
+    #[test]
+    #[should_panic(expected = "DependencyConflict")]
+    pub fn fail_conflicting_target_dependency() {
+        tryexpand::check([PASS_PATTERN])
+            .dependency("itoa", "1")
+            .skip_overwrite();
+    }
+
+    #[test]
+    #[should_panic(expected = "DependencyConflict")]
+    pub fn fail_conflicting_dev_dependency() {
+        tryexpand::check([PASS_PATTERN])
+            .dependency("either", "1")
+            .skip_overwrite();
+    }
+}
+
+mod warnings {
+    const PASS_PATTERN: &str = "tests/warnings/pass/*.rs";
+    const FAIL_PATTERN: &str = "tests/warnings/fail/*.rs";
+
+    #[test]
+    pub fn pass() {
+        tryexpand::check([PASS_PATTERN])
+            .with_warnings()
+            .expect_pass();
+    }
+
+    #[test]
+    pub fn fail() {
+        tryexpand::check([FAIL_PATTERN])
+            .deny_warnings()
+            .expect_fail();
+    }
+
+    #[test]
+    pub fn pass_auxiliary_warnings() {
+        // Only warnings within the test files themselves count as failures:
+        tryexpand::check(["tests/warnings/aux/*.rs"])
+            .deny_warnings()
+            .expect_pass();
+    }
+}
+
+mod clippy {
+    const PASS_PATTERN: &str = "tests/clippy/pass/*.rs";
+    const FAIL_PATTERN: &str = "tests/clippy/fail/*.rs";
+
+    #[test]
+    pub fn pass() {
+        tryexpand::clippy([PASS_PATTERN])
+            .clippy_warn("clippy::pedantic")
+            .expect_pass();
+    }
+
+    #[test]
+    pub fn fail() {
+        tryexpand::clippy([FAIL_PATTERN])
+            .clippy_deny("clippy::unwrap_used")
+            .expect_fail();
+    }
+}
+
+mod doc {
+    const PASS_PATTERN: &str = "tests/doc/pass/*.rs";
+    const FAIL_PATTERN: &str = "tests/doc/fail/*.rs";
+
+    #[test]
+    pub fn pass() {
+        tryexpand::doc([PASS_PATTERN]).expect_pass();
+    }
+
+    #[test]
+    pub fn fail() {
+        tryexpand::doc([FAIL_PATTERN]).deny_warnings().expect_fail();
+    }
+}
+
+mod doc_tests {
+    const PASS_PATTERN: &str = "tests/doc_tests/pass/*.rs";
+    const FAIL_PATTERN: &str = "tests/doc_tests/fail/*.rs";
+
+    #[test]
+    pub fn pass() {
+        tryexpand::run_doc_tests([PASS_PATTERN]).expect_pass();
+    }
+
+    #[test]
+    pub fn fail() {
+        tryexpand::run_doc_tests([FAIL_PATTERN])
+            .filter_stdout(
+                r"thread '([^']+)' \([^)]+\) panicked at",
+                "thread '$1' (<ID>) panicked at",
+            )
+            .expect_fail();
+    }
+}
+
+mod as_lib {
+    const PASS_PATTERN: &str = "tests/as_lib/pass/*.rs";
+    const FAIL_PATTERN: &str = "tests/as_lib/fail/*.rs";
+
+    #[test]
+    pub fn pass() {
+        tryexpand::check([PASS_PATTERN]).as_lib().expect_pass();
+    }
+
+    #[test]
+    pub fn fail() {
+        tryexpand::check([FAIL_PATTERN]).as_lib().expect_fail();
+    }
+
+    #[test]
+    pub fn expand() {
+        tryexpand::expand(["tests/as_lib/expand/*.rs"])
+            .as_lib()
+            .expect_pass();
+    }
+
+    #[test]
+    pub fn run_tests() {
+        tryexpand::run_tests(["tests/as_lib/run_tests/*.rs"])
+            .as_lib()
+            .expect_pass();
+    }
+
+    #[test]
+    #[should_panic(expected = "not supported for test files compiled as library targets")]
+    pub fn run() {
+        tryexpand::run([PASS_PATTERN]).as_lib().expect_pass();
+    }
+}
+
+mod directories {
+    const PASS_PATTERN: &str = "tests/directories/pass/*";
+    const FAIL_PATTERN: &str = "tests/directories/fail/*";
+
+    #[test]
+    pub fn pass() {
+        tryexpand::check([PASS_PATTERN]).expect_pass();
+    }
+
+    #[test]
+    pub fn fail() {
+        tryexpand::check([FAIL_PATTERN]).expect_fail();
+    }
+}
+
+mod auxiliary {
+    const PASS_PATTERN: &str = "tests/auxiliary/pass/*.rs";
+    const FAIL_PATTERN: &str = "tests/auxiliary/fail/*.rs";
+
+    #[test]
+    pub fn pass() {
+        tryexpand::check([PASS_PATTERN]).expect_pass();
+    }
+
+    #[test]
+    pub fn fail() {
+        tryexpand::check([FAIL_PATTERN]).expect_fail();
+    }
+
+    #[test]
+    pub fn fail_undeclared_as_lib() {
+        tryexpand::check(["tests/auxiliary/as_lib/*.rs"])
+            .as_lib()
+            .expect_fail();
+    }
+
+    #[test]
+    #[should_panic(expected = "DependencyConflict")]
+    pub fn fail_conflicting_dependency() {
+        tryexpand::check(["tests/auxiliary/conflict/*.rs"]).skip_overwrite();
+    }
+
+    #[test]
+    #[should_panic(expected = "ResolvingPathFailed")]
+    pub fn fail_missing() {
+        tryexpand::check(["tests/auxiliary/missing/*.rs"]).skip_overwrite();
+    }
+}
+
+mod crate_alias {
+    const PASS_PATTERN: &str = "tests/crate_alias/pass/*.rs";
+    const FAIL_PATTERN: &str = "tests/crate_alias/fail/*.rs";
+
+    #[test]
+    pub fn pass() {
+        tryexpand::check([PASS_PATTERN])
+            .crate_alias("renamed")
+            .args(["--features", "test-feature"])
+            .expect_pass();
+    }
+
+    #[test]
+    pub fn fail() {
+        tryexpand::check([FAIL_PATTERN])
+            .crate_alias("renamed")
+            .expect_fail();
+    }
+}
+
+mod cfg {
+    const PASS_PATTERN: &str = "tests/cfg/pass/*.rs";
+    const FAIL_PATTERN: &str = "tests/cfg/fail/*.rs";
+
+    #[test]
+    pub fn pass() {
+        tryexpand::check([PASS_PATTERN])
+            .cfg("my_cfg")
+            .cfg_value("backend", "x")
+            .rustflags([
+                "--cfg",
+                "from_rustflags",
+                "--check-cfg",
+                "cfg(from_rustflags)",
+            ])
+            .env("RUSTFLAGS", "--cfg from_env --check-cfg cfg(from_env)")
+            .deny_warnings()
+            .expect_pass();
+    }
+
+    #[test]
+    pub fn pass_doc() {
+        tryexpand::doc([PASS_PATTERN])
+            .cfg("my_cfg")
+            .cfg_value("backend", "x")
+            .env(
+                "RUSTDOCFLAGS",
+                "--cfg from_rustflags --check-cfg cfg(from_rustflags) --cfg from_env --check-cfg cfg(from_env)",
+            )
+            .expect_pass();
+    }
+
+    #[test]
+    pub fn fail() {
+        tryexpand::check([FAIL_PATTERN])
+            .cfg_value("backend", "x")
+            .expect_fail();
+    }
+}
+
+mod profile {
+    // Each profile has a copy of the same test file, with a snapshot of its own:
+    #[test]
+    pub fn dev() {
+        tryexpand::run(["tests/profile/dev/*.rs"]).expect_pass();
+    }
+
+    #[test]
+    pub fn release() {
+        tryexpand::run(["tests/profile/release/*.rs"])
+            .release()
+            .overflow_checks(true)
+            .expect_pass();
+    }
+
+    #[test]
+    pub fn custom() {
+        tryexpand::run(["tests/profile/custom/*.rs"])
+            .opt_level("1")
+            .debug_assertions(false)
+            .panic_abort()
+            .expect_pass();
+    }
+}
+
+mod build_script {
+    const PASS_PATTERN: &str = "tests/build_script/pass/*.rs";
+    const FAIL_PATTERN: &str = "tests/build_script/fail/*.rs";
+    const BUILD_SCRIPT: &str = "tests/build_script/support/build.rs";
+
+    #[test]
+    pub fn pass() {
+        tryexpand::check([PASS_PATTERN])
+            .build_script(BUILD_SCRIPT)
+            .expect_pass();
+    }
+
+    #[test]
+    pub fn pass_run() {
+        tryexpand::run(["tests/build_script/run/*.rs"])
+            .build_script(BUILD_SCRIPT)
+            .expect_pass();
+    }
+
+    #[test]
+    pub fn fail() {
+        tryexpand::check([FAIL_PATTERN])
+            .build_script(BUILD_SCRIPT)
+            .expect_fail();
+    }
+
+    #[test]
+    pub fn pass_auxiliary() {
+        tryexpand::check(["tests/build_script/auxiliary/*.rs"])
+            .build_script(BUILD_SCRIPT)
+            .expect_pass();
+    }
+}
+
+mod fixtures {
+    const PASS_PATTERN: &str = "tests/fixtures/pass/*.rs";
+    const FAIL_PATTERN: &str = "tests/fixtures/fail/*.rs";
+    const FIXTURES: [&str; 2] = ["tests/fixtures/data", "tests/fixtures/config.toml"];
+
+    #[test]
+    pub fn pass() {
+        tryexpand::run([PASS_PATTERN])
+            .fixtures(FIXTURES)
+            .expect_pass();
+    }
+
+    #[test]
+    pub fn pass_as_lib() {
+        tryexpand::check(["tests/fixtures/as_lib/*.rs"])
+            .as_lib()
+            .fixtures(FIXTURES)
+            .expect_pass();
+    }
+
+    #[test]
+    pub fn fail() {
+        tryexpand::check([FAIL_PATTERN])
+            .fixtures(["tests/fixtures/config.toml"])
+            // The OS's error messages are platform-specific:
+            .filter_stderr(r"(couldn't read `[^`]+`): .+", "$1: <OS ERROR>")
+            .expect_fail();
+    }
+
+    #[test]
+    #[should_panic(expected = "InvalidFixturePath")]
+    pub fn fail_outside_of_crate() {
+        tryexpand::check([PASS_PATTERN])
+            .fixtures(["../other-tests/tests/fixtures/config.toml"])
+            .expect_pass();
+    }
+
+    #[test]
+    #[should_panic(expected = "ReservedFixturePath")]
+    pub fn fail_project_dir() {
+        tryexpand::check([PASS_PATTERN])
+            .fixtures(["."])
+            .expect_pass();
+    }
+
+    #[test]
+    #[should_panic(expected = "ReservedFixturePath")]
+    pub fn fail_generated_manifest() {
+        tryexpand::check([PASS_PATTERN])
+            .fixtures(["./Cargo.toml"])
+            .expect_pass();
+    }
+
+    #[test]
+    #[should_panic(expected = "ReservedFixturePath")]
+    pub fn fail_generated_config() {
+        tryexpand::check([PASS_PATTERN])
+            .fixtures([".cargo/config.toml"])
+            .expect_pass();
+    }
+}
+
+mod json_diagnostics {
+    const PASS_PATTERN: &str = "tests/json_diagnostics/pass/*.rs";
+    const FAIL_PATTERN: &str = "tests/json_diagnostics/fail/*.rs";
+
+    #[test]
+    pub fn pass() {
+        tryexpand::check([PASS_PATTERN])
+            .with_warnings()
+            .with_json_diagnostics()
+            .expect_pass();
+    }
+
+    #[test]
+    pub fn fail() {
+        tryexpand::check([FAIL_PATTERN])
+            .with_json_diagnostics()
+            .expect_fail();
+    }
+
+    #[test]
+    pub fn expand() {
+        tryexpand::expand(["tests/json_diagnostics/expand/*.rs"])
+            .with_json_diagnostics()
+            .expect_fail();
+    }
+}
+
+mod annotations {
+    const PASS_PATTERN: &str = "tests/annotations/pass/*.rs";
+    const FAIL_PATTERN: &str = "tests/annotations/fail/*.rs";
+    const MISMATCH_PATTERN: &str = "tests/annotations/mismatch/*.rs";
+    const PROSE_PATTERN: &str = "tests/annotations/prose/*.rs";
+
+    #[test]
+    pub fn pass() {
+        tryexpand::check([PASS_PATTERN])
+            .with_warnings()
+            .without_stderr_snapshots()
+            .expect_pass();
+    }
+
+    #[test]
+    pub fn fail() {
+        tryexpand::check([FAIL_PATTERN])
+            .without_stderr_snapshots()
+            .expect_fail();
+    }
+
+    #[test]
+    #[should_panic(expected = "not found: 1: ERROR E0308")]
+    pub fn verify_fail() {
+        tryexpand::check([MISMATCH_PATTERN])
+            .without_stderr_snapshots()
+            .expect_fail();
+    }
+
+    #[test]
+    pub fn pass_prose() {
+        tryexpand::run([PROSE_PATTERN]).expect_pass();
+    }
+
+    #[test]
+    #[should_panic(expected = "UnverifiedAnnotations")]
+    pub fn fail_unverified() {
+        // We need to test with .skip_overwrite()
+        // to avoid overwriting snapshots of `fail()`:
+        tryexpand::run([FAIL_PATTERN]).skip_overwrite();
+    }
+
+    #[test]
+    #[should_panic(expected = "MisplacedAnnotations")]
+    pub fn fail_misplaced() {
+        tryexpand::check(["tests/annotations/misplaced/*"])
+            .without_stderr_snapshots()
+            .skip_overwrite();
+    }
+}
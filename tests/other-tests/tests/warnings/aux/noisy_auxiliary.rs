@@ -0,0 +1,5 @@
+// tryexpand: aux = "../support/noisy.rs"
+
+fn main() {
+    noisy::helper();
+}
@@ -0,0 +1,4 @@
+#[allow(dead_code)]
+fn NotSnakeCase() {}
+
+fn main() {}
@@ -0,0 +1,3 @@
+fn unused_helper() {}
+
+pub fn helper() {}
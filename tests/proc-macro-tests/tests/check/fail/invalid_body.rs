@@ -0,0 +1,8 @@
+use proc_macro_tests::my_proc_macro;
+
+my_proc_macro! {
+    struct Foo = 42;
+    //~^ ERROR[16-17] expected
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use proc_macro_tests::my_proc_macro;
+
+my_proc_macro! {
+    /*^*/struct 42 {}
+    //~^ ERROR expected identifier
+}
+
+fn main() {}
@@ -9,3 +9,20 @@ mod expand {
         tryexpand::expand(["tests/expand/fail/*.rs"]).expect_fail();
     }
 }
+
+mod check {
+    #[test]
+    pub fn fail() {
+        tryexpand::check(["tests/check/fail/*.rs"])
+            .without_stderr_snapshots()
+            .expect_fail();
+    }
+
+    #[test]
+    #[should_panic(expected = "not found: 4: ERROR[4:10] expected identifier")]
+    pub fn verify_fail() {
+        tryexpand::check(["tests/check/mismatch/*.rs"])
+            .without_stderr_snapshots()
+            .expect_fail();
+    }
+}
@@ -0,0 +1,3 @@
+fn main() {
+    assert!(either::is_patched());
+}
@@ -0,0 +1,9 @@
+use std::path::Path;
+
+fn main() {
+    let dir = Path::new(env!("MEMBER_CONFIG_DIR"));
+    assert!(dir.is_absolute());
+    assert!(dir.join("config").is_dir());
+
+    println!("{}", env!("MEMBER_CONFIG_VALUE"));
+}
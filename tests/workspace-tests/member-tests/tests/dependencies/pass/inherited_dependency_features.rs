@@ -0,0 +1,4 @@
+#[derive(serde::Serialize)]
+struct Inherited;
+
+fn main() {}
@@ -2,3 +2,28 @@
 pub fn it_works() {
     tryexpand::expand(["tests/expand/pass/*.rs"]);
 }
+
+#[test]
+pub fn it_applies_patches() {
+    tryexpand::check(["tests/check/pass/*.rs"]);
+}
+
+#[test]
+pub fn it_inherits_package_fields() {
+    tryexpand::run(["tests/run/pass/*.rs"]);
+}
+
+#[test]
+pub fn it_inherits_dependencies() {
+    tryexpand::check(["tests/dependencies/pass/*.rs"]);
+}
+
+#[test]
+pub fn it_inherits_lints() {
+    tryexpand::check(["tests/check/fail/*.rs"]).expect_fail();
+}
+
+#[test]
+pub fn it_merges_cargo_config() {
+    tryexpand::run(["tests/config/pass/*.rs"]);
+}
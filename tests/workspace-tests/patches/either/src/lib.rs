@@ -0,0 +1,3 @@
+pub fn is_patched() -> bool {
+    true
+}
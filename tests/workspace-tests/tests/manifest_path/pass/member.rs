@@ -0,0 +1,3 @@
+fn main() {
+    let _ = member_tests::MemberType;
+}
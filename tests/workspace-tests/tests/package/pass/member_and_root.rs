@@ -0,0 +1,4 @@
+fn main() {
+    let _ = member_tests::MemberType;
+    let _ = root_tests::RootType;
+}
@@ -2,3 +2,43 @@
 pub fn it_works() {
     tryexpand::expand(["tests/expand/pass/*.rs"]);
 }
+
+#[test]
+pub fn it_selects_package() {
+    tryexpand::check(["tests/package/pass/*.rs"])
+        .package("member-tests")
+        .workspace_dependency("root-tests")
+        .expect_pass();
+}
+
+#[test]
+pub fn it_selects_manifest_path() {
+    tryexpand::check(["tests/manifest_path/pass/*.rs"])
+        .manifest_path("member-tests/Cargo.toml")
+        .expect_pass();
+}
+
+#[test]
+pub fn it_resolves_path_dependencies_against_package() {
+    tryexpand::check(["tests/path_dependency/pass/*.rs"])
+        .package("member-tests")
+        .path_dependency("helper", "tests/support/helper")
+        .expect_pass();
+}
+
+#[test]
+#[should_panic(expected = "DependencyConflict")]
+pub fn it_rejects_conflicting_workspace_dependency() {
+    tryexpand::check(["tests/package/pass/*.rs"])
+        .package("member-tests")
+        .workspace_dependency("member-tests")
+        .skip_overwrite();
+}
+
+#[test]
+#[should_panic(expected = "CargoPackageNotFound")]
+pub fn it_rejects_non_workspace_package() {
+    tryexpand::check(["tests/package/pass/*.rs"])
+        .package("either")
+        .skip_overwrite();
+}